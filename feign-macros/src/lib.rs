@@ -1,14 +1,17 @@
 extern crate proc_macro;
 
-use crate::RequestBody::{Form, Json};
+use crate::RequestBody::{Form, Json, Multipart, Raw};
 use darling::ast::NestedMeta;
 use darling::{Error, FromMeta};
 use proc_macro::TokenStream;
 use proc_macro_error::{abort, proc_macro_error};
-use quote::quote;
+use quote::{format_ident, quote, quote_spanned};
 use syn::spanned::Spanned;
 use syn::{parse_macro_input, FnArg, TraitItemFn};
 
+#[cfg(test)]
+mod tests;
+
 /// Make a restful http client
 ///
 /// # Examples
@@ -38,41 +41,136 @@ pub fn client(args: TokenStream, input: TokenStream) -> TokenStream {
         Err(e) => return TokenStream::from(e.write_errors()),
     };
 
-    let reqwest_client_builder = match args.client_builder {
-        Some(builder) => {
-            let builder_token: proc_macro2::TokenStream = builder.parse().unwrap();
-            quote! {
-                #builder_token().await?
-            }
-        }
-        None => quote! {
-            ::feign::re_exports::reqwest::ClientBuilder::new().build()?
-        },
-    };
-
     let vis = &input.vis;
-    let name = &input.ident;
+    let trait_name = &input.ident;
     let base_host = &args.host;
     let base_path = &args.path;
 
-    let methods = input
+    let fns: Vec<&TraitItemFn> = input
         .items
         .iter()
         .filter_map(|item| match item {
             syn::TraitItem::Fn(m) => Some(m),
             _ => None,
         })
-        .map(|m| gen_method(m, args.before_send.as_ref(), &reqwest_client_builder));
+        .collect();
+
+    // a client without async methods uses reqwest::blocking
+    let blocking = fns
+        .first()
+        .is_some_and(|method| method.sig.asyncness.is_none());
+    if let Some(method) = fns
+        .iter()
+        .find(|method| method.sig.asyncness.is_none() != blocking)
+    {
+        abort!(
+            &method.sig.span(),
+            "async and blocking methods can not be mixed in one client"
+        )
+    }
+    let (reqwest, feign, await_) = if blocking {
+        (
+            quote! {::feign::blocking},
+            quote! {::feign::blocking},
+            quote! {},
+        )
+    } else {
+        (
+            quote! {::feign::re_exports::reqwest},
+            quote! {::feign},
+            quote! {.await},
+        )
+    };
+
+    let reqwest_client_builder = match &args.client_builder {
+        Some(builder) => {
+            let builder_token: proc_macro2::TokenStream = builder.parse().unwrap();
+            quote! {
+                #builder_token()#await_.map_err(::feign::Error::hook)
+            }
+        }
+        None => {
+            let connect_timeout = args.connect_timeout.as_ref().map(|timeout| {
+                let millis = timeout.millis;
+                quote! {
+                    .connect_timeout(::std::time::Duration::from_millis(#millis))
+                }
+            });
+            quote! {
+                #reqwest::ClientBuilder::new()
+                    #connect_timeout
+                    .build()
+                    .map_err(::feign::Error::from)
+            }
+        }
+    };
+    if let (Some(_), Some(timeout)) = (&args.client_builder, &args.connect_timeout) {
+        abort!(
+            timeout.span,
+            "connect_timeout can not be used with client_builder, set it on the reqwest::ClientBuilder"
+        )
+    }
+
+    // the reqwest client is built on the first request
+    let (client_cell, reqwest_client) = if blocking {
+        (
+            quote! {::std::sync::OnceLock},
+            quote! {
+                fn reqwest_client(&self) -> Result<&#reqwest::Client, ::feign::Error> {
+                    if let Some(client) = self.client.get() {
+                        return Ok(client);
+                    }
+                    let client = #reqwest_client_builder?;
+                    Ok(self.client.get_or_init(|| client))
+                }
+            },
+        )
+    } else {
+        (
+            quote! {::feign::re_exports::tokio::sync::OnceCell},
+            quote! {
+                async fn reqwest_client(&self) -> Result<&#reqwest::Client, ::feign::Error> {
+                    self.client
+                        .get_or_try_init(|| async { #reqwest_client_builder })
+                        .await
+                }
+            },
+        )
+    };
+
+    let methods = fns.iter().map(|m| gen_method(m, &args));
 
-    let builder_name: proc_macro2::TokenStream =
-        format!("{}Builder", quote! {#name}).parse().unwrap();
+    // keep the trait and implement it with a struct
+    let keep_trait = args.keep_trait || args.mock;
+    let name = if keep_trait {
+        format_ident!("{}Impl", trait_name)
+    } else {
+        trait_name.clone()
+    };
+    let builder_name = format_ident!("{}Builder", trait_name);
+    let trait_tokens = if keep_trait {
+        gen_trait(&input, &name, &fns)
+    } else {
+        quote! {}
+    };
+    let mock_tokens = if args.mock {
+        gen_mock(&input, &fns)
+    } else {
+        quote! {}
+    };
 
     let tokens = quote! {
 
+        #trait_tokens
+
+        #mock_tokens
+
         #[derive(Debug)]
         #vis struct #name<T=()> {
             host: std::sync::Arc<dyn feign::Host>,
             path: String,
+            client: #client_cell<#reqwest::Client>,
+            middlewares: #feign::Middlewares,
             state: feign::State<T>,
         }
 
@@ -81,6 +179,8 @@ pub fn client(args: TokenStream, input: TokenStream) -> TokenStream {
                 #name::<()>{
                     host: std::sync::Arc::new(String::from(#base_host)),
                     path: String::from(#base_path),
+                    client: #client_cell::new(),
+                    middlewares: #feign::Middlewares::new(),
                     state: feign::State::new(()),
                 }
             }
@@ -91,6 +191,8 @@ pub fn client(args: TokenStream, input: TokenStream) -> TokenStream {
         }
 
         impl<T> #name<T> where T: std::any::Any + core::marker::Send + core::marker::Sync + 'static{
+            #reqwest_client
+
             #(#methods)*
         }
 
@@ -117,10 +219,30 @@ pub fn client(args: TokenStream, input: TokenStream) -> TokenStream {
                 self
             }
 
+            /// Send with `client`, the `client_builder` and `connect_timeout` of the client attribute are not used
+            pub fn with_client(mut self, client: #reqwest::Client) -> Self {
+                self.0.client = #client_cell::from(client);
+                self
+            }
+
+            /// Add a middleware, the first one added runs first
+            pub fn layer(mut self, middleware: impl #feign::Middleware) -> Self {
+                self.0.middlewares.push(middleware);
+                self
+            }
+
+            /// Send the requests with `transport` instead of the reqwest client
+            pub fn with_transport(mut self, transport: impl #feign::Transport) -> Self {
+                self.0.middlewares.set_transport(transport);
+                self
+            }
+
             pub fn with_state<S: std::any::Any + core::marker::Send + core::marker::Sync + 'static>(mut self, state: S) -> #builder_name<S> {
                 #builder_name(#name::<S>{
                     host: self.0.host,
                     path: self.0.path,
+                    client: self.0.client,
+                    middlewares: self.0.middlewares,
                     state: feign::State::new(state),
                 })
             }
@@ -130,31 +252,220 @@ pub fn client(args: TokenStream, input: TokenStream) -> TokenStream {
     tokens.into()
 }
 
-/// Gen feign methods
-fn gen_method(
-    method: &TraitItemFn,
-    before_send: Option<&String>,
-    reqwest_client_builder: &proc_macro2::TokenStream,
+/// Attribute of a method or an argument consumed by the client macro
+fn is_feign_attr(attr: &syn::Attribute) -> bool {
+    attr.path().get_ident().is_some_and(|ident| {
+        http_method_from_ident(ident).is_some()
+            || matches!(
+                &*ident.to_string(),
+                "path"
+                    | "query"
+                    | "json"
+                    | "form"
+                    | "multipart"
+                    | "part"
+                    | "body"
+                    | "header"
+                    | "headers"
+                    | "args"
+            )
+    })
+}
+
+/// Typed args of a method without the feign attributes, with names to pass them on
+fn plain_args(method: &TraitItemFn) -> Vec<(syn::Ident, syn::PatType)> {
+    method
+        .sig
+        .inputs
+        .iter()
+        .filter_map(|fn_arg| match fn_arg {
+            FnArg::Receiver(_) => None,
+            FnArg::Typed(a) => Some(a),
+        })
+        .enumerate()
+        .map(|(index, arg)| {
+            let ident = match &*arg.pat {
+                syn::Pat::Ident(pat) => pat.ident.clone(),
+                _ => format_ident!("arg{}", index),
+            };
+            let mut arg = arg.clone();
+            arg.attrs.retain(|attr| !is_feign_attr(attr));
+            arg.pat = Box::new(syn::parse_quote! {#ident});
+            (ident, arg)
+        })
+        .collect()
+}
+
+/// The trait without the feign attributes, and its implementation delegating to the inherent methods
+fn gen_trait(
+    input: &syn::ItemTrait,
+    impl_name: &syn::Ident,
+    methods: &[&TraitItemFn],
 ) -> proc_macro2::TokenStream {
-    if method.sig.asyncness.is_none() {
-        abort!(
-            &method.sig.span(),
-            "Non-asynchronous calls are not currently supported"
-        )
+    let trait_name = &input.ident;
+    let mut item = input.clone();
+    for trait_item in item.items.iter_mut() {
+        if let syn::TraitItem::Fn(method) = trait_item {
+            method.attrs.retain(|attr| !is_feign_attr(attr));
+            for fn_arg in method.sig.inputs.iter_mut() {
+                if let FnArg::Typed(arg) = fn_arg {
+                    arg.attrs.retain(|attr| !is_feign_attr(attr));
+                }
+            }
+        }
+    }
+
+    let impls = methods.iter().map(|method| {
+        let ident = &method.sig.ident;
+        let output = &method.sig.output;
+        let asyncness = &method.sig.asyncness;
+        let await_ = asyncness.map(|_| quote! {.await});
+        let (names, args): (Vec<_>, Vec<_>) = plain_args(method).into_iter().unzip();
+        quote! {
+            #asyncness fn #ident(&self, #(#args),*) #output {
+                <#impl_name<T>>::#ident(self, #(#names),*)#await_
+            }
+        }
+    });
+
+    quote! {
+        #[::feign::re_exports::async_trait::async_trait]
+        #item
+
+        #[::feign::re_exports::async_trait::async_trait]
+        impl<T> #trait_name for #impl_name<T> where T: std::any::Any + core::marker::Send + core::marker::Sync + 'static {
+            #(#impls)*
+        }
     }
+}
+
+/// `MockXxx` implementing the trait with a programmable function per method
+fn gen_mock(input: &syn::ItemTrait, methods: &[&TraitItemFn]) -> proc_macro2::TokenStream {
+    let vis = &input.vis;
+    let trait_name = &input.ident;
+    let mock_name = format_ident!("Mock{}", trait_name);
+
+    let mut fields = vec![];
+    let mut setters = vec![];
+    let mut impls = vec![];
+    for method in methods {
+        let ident = &method.sig.ident;
+        let on_ident = format_ident!("on_{}", ident);
+        let output = &method.sig.output;
+        let out = match output {
+            syn::ReturnType::Default => quote! {()},
+            syn::ReturnType::Type(_, ty) => quote! {#ty},
+        };
+        let (names, args): (Vec<_>, Vec<_>) = plain_args(method).into_iter().unzip();
+        // the closures take `impl Trait` arguments boxed
+        let (types, values): (Vec<_>, Vec<_>) = names
+            .iter()
+            .zip(&args)
+            .map(|(name, arg)| match &*arg.ty {
+                syn::Type::ImplTrait(ty) => {
+                    let bounds = &ty.bounds;
+                    let lifetime = match bounds
+                        .iter()
+                        .any(|bound| matches!(bound, syn::TypeParamBound::Lifetime(_)))
+                    {
+                        true => quote! {},
+                        false => quote! {+ 'static},
+                    };
+                    (
+                        quote! {::std::pin::Pin<::std::boxed::Box<dyn #bounds #lifetime>>},
+                        quote! {::std::boxed::Box::pin(#name)},
+                    )
+                }
+                ty if has_impl_trait(quote! {#ty}) => abort!(
+                    &ty.span(),
+                    "a mock takes `impl Trait` only as the whole type of an argument"
+                ),
+                ty => (quote! {#ty}, quote! {#name}),
+            })
+            .unzip();
+        let not_programmed = format!("{}::{} is not programmed", mock_name, ident);
+        fields.push(quote! {
+            #ident: std::sync::Mutex<Option<Box<dyn FnMut(#(#types),*) -> #out + core::marker::Send>>>
+        });
+        setters.push(quote! {
+            pub fn #on_ident(self, f: impl FnMut(#(#types),*) -> #out + core::marker::Send + 'static) -> Self {
+                *self.#ident.lock().unwrap() = Some(Box::new(f));
+                self
+            }
+        });
+        let asyncness = &method.sig.asyncness;
+        impls.push(quote! {
+            #asyncness fn #ident(&self, #(#args),*) #output {
+                let mut f = self.#ident.lock().unwrap();
+                match f.as_mut() {
+                    Some(f) => f(#(#values),*),
+                    None => panic!(#not_programmed),
+                }
+            }
+        });
+    }
+    let idents: Vec<_> = methods.iter().map(|method| &method.sig.ident).collect();
+
+    quote! {
+        #vis struct #mock_name {
+            #(#fields,)*
+        }
+
+        impl #mock_name {
+            pub fn new() -> Self {
+                Self {
+                    #(#idents: std::sync::Mutex::new(None),)*
+                }
+            }
+
+            #(#setters)*
+        }
+
+        impl Default for #mock_name {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        #[::feign::re_exports::async_trait::async_trait]
+        impl #trait_name for #mock_name {
+            #(#impls)*
+        }
+    }
+}
+
+/// Whether `tokens` has an `impl Trait` type
+fn has_impl_trait(tokens: proc_macro2::TokenStream) -> bool {
+    tokens.into_iter().any(|token| match token {
+        proc_macro2::TokenTree::Ident(ident) => ident == "impl",
+        proc_macro2::TokenTree::Group(group) => has_impl_trait(group.stream()),
+        _ => false,
+    })
+}
+
+/// Gen feign methods
+fn gen_method(method: &TraitItemFn, client: &ClientArgs) -> proc_macro2::TokenStream {
+    // without async, the method is generated on reqwest::blocking
+    let asyncness = &method.sig.asyncness;
+    let blocking = asyncness.is_none();
+    let await_ = if blocking {
+        quote! {}
+    } else {
+        quote! {.await}
+    };
 
     let name = &method.sig.ident;
     let inputs = &method.sig.inputs;
     let output = &method.sig.output;
-    let attr = method.attrs.iter().next();
-    let http_method_ident = match attr.map(|a| a.path().get_ident()).flatten() {
+    let attr = method.attrs.first();
+    let http_method_ident = match attr.and_then(|a| a.path().get_ident()) {
         Some(ident) => ident,
         None => {
             abort!(&method.span(), "Expects an http method")
         }
     };
 
-    let _http_method = if let Some(m) = http_method_from_ident(http_method_ident) {
+    let http_method = if let Some(m) = http_method_from_ident(http_method_ident) {
         m
     } else {
         abort!(
@@ -163,7 +474,7 @@ fn gen_method(
         )
     };
 
-    let _http_method_token = http_method_to_token(_http_method);
+    let http_method_token = http_method_to_token(http_method);
 
     let request: Request = match Request::from_meta(&attr.unwrap().meta) {
         Ok(v) => v,
@@ -175,6 +486,8 @@ fn gen_method(
     let mut path_variables = Vec::new();
     let mut querys = Vec::new();
     let mut body = None;
+    let mut parts = Vec::new();
+    let mut header_args = Vec::new();
     let mut headers = None;
     let mut args = None;
 
@@ -190,15 +503,58 @@ fn gen_method(
             FnArg::Typed(ty) => Some((ty, &ty.attrs.first()?.path().segments.first()?.ident)),
         })
         .for_each(|(ty, p)| match &*p.to_string() {
-            "path" => path_variables.push(&ty.pat),
-            "query" => querys.push(&ty.pat),
+            "path" => match &*ty.pat {
+                syn::Pat::Ident(pat) => {
+                    path_variables.push((&pat.ident, is_raw_path(&ty.attrs[0])))
+                }
+                _ => abort!(&ty.pat.span(), "path variable must be an identifier"),
+            },
+            "query" => match &*ty.pat {
+                syn::Pat::Ident(pat) => {
+                    querys.push((&pat.ident, QueryArg::from_attr(&ty.attrs[0], &ty.ty)))
+                }
+                _ => abort!(&ty.pat.span(), "query argument must be an identifier"),
+            },
             "json" => match body {
                 None => body = Some(Json(&ty.pat)),
-                _ => abort!(&ty.span(), "json or form only once"),
+                _ => abort!(&ty.span(), "json, form, multipart or body only once"),
             },
             "form" => match body {
                 None => body = Some(Form(&ty.pat)),
-                _ => abort!(&ty.span(), "json or form only once"),
+                _ => abort!(&ty.span(), "json, form, multipart or body only once"),
+            },
+            "multipart" => match body {
+                None | Some(Multipart(None)) => body = Some(Multipart(Some(&ty.pat))),
+                _ => abort!(&ty.span(), "json, form, multipart or body only once"),
+            },
+            "body" => match (&body, &*ty.pat) {
+                (None, syn::Pat::Ident(pat)) => {
+                    let mut arg = BodyArg::from_attr(&ty.attrs[0]);
+                    arg.stream |= is_impl_stream(&ty.ty);
+                    arg.raw |= arg.stream || is_raw_type(&ty.ty);
+                    if arg.stream && blocking {
+                        abort!(&ty.span(), "streaming bodies need an async method");
+                    }
+                    body = Some(Raw(&pat.ident, arg));
+                }
+                (None, _) => abort!(&ty.pat.span(), "body argument must be an identifier"),
+                _ => abort!(&ty.span(), "json, form, multipart or body only once"),
+            },
+            "part" => match (&body, &*ty.pat) {
+                (None | Some(Multipart(_)), syn::Pat::Ident(pat)) => {
+                    parts.push((&pat.ident, PartArg::from_attr(&ty.attrs[0])));
+                    body.get_or_insert(Multipart(None));
+                }
+                (None | Some(Multipart(_)), _) => {
+                    abort!(&ty.pat.span(), "part argument must be an identifier")
+                }
+                _ => abort!(&ty.span(), "part can not be sent with json or form"),
+            },
+            "header" => match &*ty.pat {
+                syn::Pat::Ident(pat) => {
+                    header_args.push((&pat.ident, header_arg_name(&ty.attrs[0])))
+                }
+                _ => abort!(&ty.pat.span(), "header argument must be an identifier"),
             },
             "headers" => match headers {
                 None => headers = Some(&ty.pat),
@@ -214,28 +570,111 @@ fn gen_method(
             ),
         });
 
-    let path_variables = if path_variables.is_empty() {
-        quote! {}
-    } else {
-        let mut stream = proc_macro2::TokenStream::new();
-        for pv in path_variables {
-            let id = format!("<{}>", quote! {#pv});
-            stream.extend(quote! {
-                .replace(#id, format!("{}", #pv).as_str())
-            });
+    // the template is checked here, the variables are encoded when the request is sent
+    let template = match parse_path_template(req_path) {
+        Ok(template) => template,
+        Err(err) => abort!(&attr.unwrap().span(), err),
+    };
+    for (ident, _) in &path_variables {
+        if !template
+            .iter()
+            .any(|part| matches!(part, PathPart::Variable(name) if *ident == name))
+        {
+            abort!(
+                &ident.span(),
+                format!("#[path] {} is not in the path {:?}", ident, req_path)
+            );
         }
-        stream
+    }
+    let path_parts = template.iter().map(|part| match part {
+        PathPart::Literal(literal) => quote! {
+            request_path.push_str(#literal);
+        },
+        PathPart::Variable(name) => match path_variables.iter().find(|(ident, _)| *ident == name) {
+            Some((ident, false)) => quote! {
+                request_path.push_str(&::feign::encode_path(&#ident));
+            },
+            Some((ident, true)) => quote! {
+                request_path.push_str(&#ident.to_string());
+            },
+            // left for the path of #[args]
+            None if args.is_some() => {
+                let placeholder = format!("<{}>", name);
+                quote! {
+                    request_path.push_str(#placeholder);
+                }
+            }
+            None => abort!(
+                &attr.unwrap().span(),
+                format!("path variable <{}> has no #[path] argument", name)
+            ),
+        },
+    });
+    let path_variables = quote! {
+        #(#path_parts)*
     };
 
-    let mut query = if querys.is_empty() {
-        quote! {}
-    } else {
-        quote! {
-            req = req.query(&[#(#querys),*]);
+    // built once, every attempt sends the same pairs
+    let query_pairs = querys.iter().map(|(ident, arg)| {
+        let join = match &arg.join {
+            Some(join) => quote! {Some(#join)},
+            None => quote! {None},
+        };
+        if arg.pair {
+            quote! {
+                request_query.flatten(&[&#ident], None)?;
+            }
+        } else if arg.flatten {
+            quote! {
+                request_query.flatten(&#ident, #join)?;
+            }
+        } else {
+            let name = arg.name.clone().unwrap_or_else(|| ident.to_string());
+            quote! {
+                request_query.push(#name, &#ident, #join)?;
+            }
+        }
+    });
+    let build_query = quote! {
+        let mut request_query = ::feign::Query::new();
+        #(#query_pairs)*
+    };
+    let mut query = quote! {
+        if !request_query.is_empty() {
+            req = req.query(request_query.pairs());
         }
     };
 
-    let (mut req_body, mut req_body_enum) = match body {
+    let codec = request
+        .codec
+        .as_ref()
+        .or(client.codec.as_ref())
+        .map(|codec| &codec.0);
+    let encode = matches!(&body, Some(Raw(_, arg)) if !arg.raw);
+    // type errors of the codec point to the attribute
+    let (request_codec, accept) = match codec {
+        Some(codec) => (
+            quote_spanned! {codec.span()=>
+                let request_codec = #codec;
+            },
+            quote_spanned! {codec.span()=>
+                request_headers.insert(
+                    ::feign::re_exports::reqwest::header::ACCEPT,
+                    ::feign::Codec::accept(&request_codec),
+                )?;
+            },
+        ),
+        None if encode => (
+            quote! {
+                let request_codec = ::feign::codec::Json;
+            },
+            quote! {},
+        ),
+        None => (quote! {}, quote! {}),
+    };
+
+    let mut prepare_body = quote! {};
+    let (mut req_body, mut req_body_enum) = match &body {
         None => (quote! {}, quote! {feign::RequestBody::<()>::None}),
         Some(Form(form)) => (
             quote! {
@@ -249,14 +688,119 @@ fn gen_method(
             },
             quote! {feign::RequestBody::Json(#json)},
         ),
+        Some(Multipart(form)) => {
+            let multipart = if blocking {
+                quote! {::feign::blocking::multipart}
+            } else {
+                quote! {::feign::multipart}
+            };
+            let form = match form {
+                Some(form) => quote! {#form},
+                None => quote! {#multipart::Form::new()},
+            };
+            let parts = parts.iter().map(|(ident, arg)| {
+                let name = arg.name.clone().unwrap_or_else(|| ident.to_string());
+                let filename = arg.filename.as_ref().map(|filename| {
+                    quote! {
+                        let part = part.file_name(#filename);
+                    }
+                });
+                let content_type = arg.content_type.as_ref().map(|content_type| {
+                    quote! {
+                        let part = part.mime_str(#content_type).map_err(::feign::Error::encode)?;
+                    }
+                });
+                quote! {
+                    let part = #multipart::IntoPart::into_part(#ident)#await_?;
+                    #filename
+                    #content_type
+                    request_form = request_form.part(#name, part);
+                }
+            });
+            (
+                quote! {
+                    let mut request_form = #form;
+                    #(#parts)*
+                    req = req.multipart(request_form);
+                },
+                quote! {feign::RequestBody::<()>::Multipart},
+            )
+        }
+        Some(Raw(ident, arg)) if arg.stream => {
+            let content_type = arg
+                .content_type
+                .clone()
+                .unwrap_or_else(|| "application/octet-stream".to_string());
+            (
+                quote! {
+                    req = req
+                        .header(::feign::re_exports::reqwest::header::CONTENT_TYPE, #content_type)
+                        .body(::feign::re_exports::reqwest::Body::wrap_stream(#ident));
+                },
+                quote! {feign::RequestBody::<()>::Stream},
+            )
+        }
+        Some(Raw(ident, arg)) if !arg.raw => {
+            let content_type = match &arg.content_type {
+                Some(content_type) => quote! {#content_type},
+                None => quote! {::feign::Codec::content_type(&request_codec).to_string()},
+            };
+            // encoded once, every attempt sends a clone
+            prepare_body = quote! {
+                let request_content_type = #content_type;
+                let request_body = ::feign::Codec::encode(&request_codec, &#ident)
+                    .map_err(::feign::Error::Encode)?;
+            };
+            (
+                quote! {
+                    req = req
+                        .header(::feign::re_exports::reqwest::header::CONTENT_TYPE, request_content_type.as_str())
+                        .body(request_body.clone());
+                },
+                quote! {feign::RequestBody::<()>::Raw(request_body.clone())},
+            )
+        }
+        Some(Raw(ident, arg)) => {
+            let content_type = match &arg.content_type {
+                Some(content_type) => quote! {#content_type},
+                None => quote! {::feign::IntoBody::content_type(&#ident)},
+            };
+            // converted once, every attempt sends a clone
+            prepare_body = quote! {
+                let request_content_type = #content_type;
+                let request_body = ::feign::IntoBody::into_body(#ident);
+            };
+            (
+                quote! {
+                    req = req
+                        .header(::feign::re_exports::reqwest::header::CONTENT_TYPE, request_content_type)
+                        .body(request_body.clone());
+                },
+                quote! {feign::RequestBody::<()>::Raw(request_body.clone())},
+            )
+        }
     };
 
+    // later ones replace the headers set before, see feign::Headers
+    let static_headers = client
+        .headers
+        .iter()
+        .chain(request.headers.iter())
+        .flat_map(|headers| headers.0.iter())
+        .map(|(name, value)| {
+            quote! {
+                request_headers.insert(#name, #value)?;
+            }
+        });
+    let header_args = header_args.iter().map(|(ident, name)| {
+        quote! {
+            request_headers.insert_value(#name, &#ident)?;
+        }
+    });
     let mut headers = match headers {
         None => quote! {},
         Some(headers) => quote! {
-                for header in #headers {
-                    req = req.header(header.0,header.1);
-                }
+            request_headers.extend(#headers)?;
         },
     };
 
@@ -268,6 +812,7 @@ fn gen_method(
             }
         };
         query = quote! {
+            #query
             if let Some(query) = #args.query() {
                 req = req.query(&query);
             }
@@ -279,7 +824,7 @@ fn gen_method(
                 match #args.body() {
                     feign::RequestBody::None => {},
                     _ => {
-                        return Err(feign::re_exports::anyhow::anyhow!("json or form can only once"));
+                        return Err(::feign::Error::encode("json or form can only once"));
                     },
                 }
             };
@@ -294,6 +839,8 @@ fn gen_method(
                     feign::RequestBody::Json(json) => {
                         req = req.json(json);
                     },
+                    // Args has no other bodies
+                    _ => {},
                 }
             };
             req_body_enum = quote! {req_body};
@@ -301,9 +848,7 @@ fn gen_method(
         headers = quote! {
             #headers
             if let Some(headers) = #args.headers() {
-                for header in headers {
-                    req = req.header(header.0, header.1);
-                }
+                request_headers.extend(headers)?;
             }
         };
     };
@@ -320,47 +865,433 @@ fn gen_method(
         })
         .collect::<syn::punctuated::Punctuated<_, syn::Token![,]>>();
 
-    let before_send_builder = match before_send {
+    let before_send_builder = match &client.before_send {
         Some(builder) => {
             let builder_token: proc_macro2::TokenStream = builder.clone().parse().unwrap();
             quote! {
                 let req = #builder_token(
                             req,
                             #req_body_enum,
-                            self.state.downcast_ref()?,
-                        ).await?;
+                            self.state.downcast_ref().map_err(::feign::Error::hook)?,
+                        )#await_.map_err(::feign::Error::hook)?;
             }
         }
         None => quote! {},
     };
 
-    let deserialize = match request.deserialize {
+    // the hook sees every response before the status check, transport errors have no response
+    let (request_url, started, after_receive, reconnect_after_receive) = match &client.after_receive
+    {
+        Some(hook) => {
+            let hook_token: proc_macro2::TokenStream = hook.parse().unwrap();
+            let method_name = name.to_string();
+            (
+                quote! {
+                    let request_url = url.clone();
+                },
+                quote! {
+                    let started = ::std::time::Instant::now();
+                },
+                quote! {
+                    let response = match response {
+                        Ok(response) => #hook_token(
+                                    response,
+                                    &::feign::RequestMeta {
+                                        name: #method_name,
+                                        method: #http_method_token,
+                                        url: &request_url,
+                                        path: &request_path,
+                                        attempt,
+                                        elapsed: started.elapsed(),
+                                    },
+                                    self.state.downcast_ref().map_err(::feign::Error::hook)?,
+                                )#await_.map_err(::feign::Error::hook),
+                        Err(err) => Err(err),
+                    };
+                },
+                // event streams call it again when they reconnect
+                quote! {
+                    .with_after_receive(#method_name, {
+                        let state = self.state.clone();
+                        move |response, request| {
+                            let state = state.clone();
+                            ::std::boxed::Box::pin(async move {
+                                #hook_token(
+                                    response,
+                                    request,
+                                    state.downcast_ref().map_err(::feign::Error::hook)?,
+                                ).await.map_err(::feign::Error::hook)
+                            })
+                        }
+                    })
+                },
+            )
+        }
+        None => (quote! {}, quote! {}, quote! {}, quote! {}),
+    };
+
+    let deserialize = match &request.deserialize {
+        None if codec.is_some() => quote! {
+            ::feign::Codec::decode(&request_codec, content_type.as_deref(), &bytes)
+        },
         None => quote! {::feign::re_exports::serde_json::from_slice(&bytes)},
-        Some(deserialize) => {
+        // called with the response, type errors point to the attribute
+        Some(DeserializeArg::Decoder(decoder)) => {
+            // the decoded type is named, so a wrong decoder is an error on the attribute
+            let decoded = match response_kind(output) {
+                ResponseKind::Response => ok_type(output).and_then(first_type_argument),
+                _ => ok_type(output),
+            };
+            let decoder_trait = match decoded {
+                Some(decoded) => quote! {::feign::Decoder<#decoded>},
+                None => quote! {::feign::Decoder<_>},
+            };
+            quote_spanned! {decoder.span()=>
+                <_ as #decoder_trait>::decode(
+                    &#decoder,
+                    ::feign::ResponseView { status, headers: &headers, body: &bytes },
+                )
+            }
+        }
+        Some(DeserializeArg::Function(deserialize)) => {
             let builder_token: proc_macro2::TokenStream = deserialize.parse().unwrap();
-            quote! {#builder_token(&bytes).await}
+            quote! {#builder_token(&bytes)#await_}
         }
     };
+    // the decoder reads the headers of the response
+    let decoder_headers = match &request.deserialize {
+        Some(DeserializeArg::Decoder(_)) => Some(quote! {
+            let headers = response.headers().clone();
+        }),
+        _ => None,
+    };
+    let deserialize = quote! {
+        #deserialize.map_err(|err| ::feign::Error::decode(bytes.clone(), err))
+    };
 
-    quote! {
-        pub async fn #name(&self, #inputs) #output {
-            let mut request_path = String::from(#req_path)#path_variables;
-            #args_path
-            let url = format!("{}{}{}", self.host, self.path, request_path);
-            let mut req = #reqwest_client_builder
-                        .#http_method_ident(url.as_str());
+    // the codec decodes by the content type of the response
+    let content_type = codec.as_ref().map(|_| {
+        quote! {
+            let content_type = response
+                .headers()
+                .get(::feign::re_exports::reqwest::header::CONTENT_TYPE)
+                .and_then(|content_type| content_type.to_str().ok())
+                .map(str::to_string);
+        }
+    });
+
+    let response_kind = response_kind(output);
+    if blocking
+        && matches!(
+            response_kind,
+            ResponseKind::Stream | ResponseKind::Lines | ResponseKind::Read | ResponseKind::Events
+        )
+    {
+        abort!(&output.span(), "streaming responses need an async method");
+    }
+    let decode_response = match response_kind {
+        ResponseKind::Body => quote! {
+            #content_type
+            #decoder_headers
+            let bytes = response.bytes()#await_?;
+            #deserialize
+        },
+        ResponseKind::Response => quote! {
+            let headers = response.headers().clone();
+            let url = response.url().clone();
+            #content_type
+            let bytes = response.bytes()#await_?;
+            #deserialize.map(|body| ::feign::Response { status, headers, url, body })
+        },
+        ResponseKind::Raw => quote! {
+            let headers = response.headers().clone();
+            let url = response.url().clone();
+            let bytes = response.bytes()#await_?;
+            Ok(::feign::Response { status, headers, url, body: bytes })
+        },
+        ResponseKind::Stream => quote! {
+            Ok(::feign::ByteStream::from_response(response))
+        },
+        ResponseKind::Lines => quote! {
+            Ok(::feign::JsonLines::new(::feign::ByteStream::from_response(response)))
+        },
+        ResponseKind::Read => quote! {
+            Ok(::feign::ByteStream::from_response(response).into_async_read())
+        },
+        ResponseKind::Events => quote! {
+            let reconnect = reconnect.map(|request| {
+                ::feign::Reconnect::new(
+                    self.host.clone(),
+                    #http_method_token,
+                    self.path.clone(),
+                    request_path.clone(),
+                    request,
+                    self.middlewares.clone(),
+                )
+                #reconnect_after_receive
+            });
+            Ok(::feign::EventStream::new(response, reconnect))
+        },
+    };
+
+    // server-sent events keep the request to reconnect
+    let (accept_events, reconnect, keep_reconnect) = match response_kind {
+        ResponseKind::Events => (
+            quote! {
+                request_headers.insert(::feign::re_exports::reqwest::header::ACCEPT, "text/event-stream")?;
+            },
+            quote! {
+                let mut reconnect = None;
+            },
+            quote! {
+                reconnect = req.try_clone();
+            },
+        ),
+        _ => (quote! {}, quote! {}, quote! {}),
+    };
+
+    let timeout = request
+        .timeout
+        .as_ref()
+        .or(client.timeout.as_ref())
+        .map(|timeout| {
+            let millis = timeout.millis;
+            quote! {
+                req = req.timeout(::std::time::Duration::from_millis(#millis));
+            }
+        });
+
+    let attempt = quote! {
+            let selected = ::feign::Selected::new(
+                &*self.host,
+                &::feign::RequestContext {
+                    method: #http_method_token,
+                    path: &request_path,
+                },
+            );
+            let url = format!("{}{}{}", selected.host(), self.path, request_path);
+            let url = ::feign::re_exports::reqwest::Url::parse(&url)
+                .map_err(|source| ::feign::Error::InvalidUrl { url, source })?;
+            #request_url
+            let mut req = self
+                        .reqwest_client()
+                        #await_?
+                        .#http_method_ident(url);
+            #timeout
             #query
             #req_body
-            #headers
+            if !request_headers.is_empty() {
+                req = req.headers(request_headers.header_map().clone());
+            }
             #before_send_builder
-            let bytes = req
-                .send()
-                .await?
-                .error_for_status()?
-                .bytes()
-                .await?;
-            Ok(#deserialize?)
+            #keep_reconnect
+            #started
+            let response = self.middlewares.send(req)#await_;
+            selected.finish(match &response {
+                Ok(response) if !response.status().is_server_error() => ::feign::Outcome::Success,
+                _ => ::feign::Outcome::Failure,
+            });
+            #after_receive
+    };
+
+    let sleep = if blocking {
+        quote! {::std::thread::sleep(backoff)}
+    } else {
+        quote! {::feign::re_exports::tokio::time::sleep(backoff).await}
+    };
+
+    // multipart parts and streams are moved into the request, it is sent once
+    let once = match &body {
+        Some(Multipart(_)) => Some("multipart bodies"),
+        Some(Raw(_, arg)) if arg.stream => Some("streaming bodies"),
+        _ => None,
+    };
+    let retry = match (once, &request.retry) {
+        (Some(once), Some(_)) => abort!(
+            &attr.unwrap().span(),
+            format!("{} are sent once and can not be retried", once)
+        ),
+        (Some(_), None) => None,
+        _ => request.retry.as_ref().or(client.retry.as_ref()),
+    };
+
+    // every attempt selects the host and builds the request again
+    let first_attempt = client.after_receive.as_ref().map(|_| {
+        quote! {
+            let attempt: u32 = 1;
+        }
+    });
+    let send = match retry {
+        None => quote! {
+            let response = {
+                #first_attempt
+                #attempt
+                response?
+            };
+        },
+        Some(retry) => {
+            let retry_token: proc_macro2::TokenStream = retry.parse().unwrap();
+            quote! {
+                let retry_policy: ::feign::RetryPolicy = #retry_token();
+                let mut attempt: u32 = 0;
+                let response = loop {
+                    attempt += 1;
+                    #attempt
+                    let head = response.as_ref().map(|response| (response.status(), response.headers()));
+                    if let Some(backoff) = retry_policy.retry_after(#http_method_token, attempt, head) {
+                        #sleep;
+                        continue;
+                    }
+                    break response?;
+                };
+            }
         }
+    };
+
+    let error_decoder = match request.error_decoder {
+        None => quote! {},
+        Some(error_decoder) => {
+            let decoder_token: proc_macro2::TokenStream = error_decoder.parse().unwrap();
+            quote! {
+                Err(::feign::Error::Status(status)) => {
+                    let ::feign::StatusError { code, headers, body } = *status;
+                    return Err(::core::convert::From::from(#decoder_token(code, headers, body)#await_));
+                }
+            }
+        }
+    };
+
+    // run the request in a block returning feign::Error, so the error decoder can map it
+    let run = quote! {
+        let mut request_path = String::new();
+        #path_variables
+        #args_path
+        #build_query
+        #request_codec
+        #prepare_body
+        let mut request_headers = ::feign::Headers::new();
+        #accept
+        #accept_events
+        #(#static_headers)*
+        #(#header_args)*
+        #headers
+        #reconnect
+        #send
+        let status = response.status();
+        if status.is_client_error() || status.is_server_error() {
+            let headers = response.headers().clone();
+            // a body that can not be read fails as a transport error
+            let body = response.bytes()#await_?;
+            return Err(::feign::Error::Status(::std::boxed::Box::new(::feign::StatusError {
+                code: status,
+                headers,
+                body,
+            })));
+        }
+        #decode_response
+    };
+    let (run, allow) = if blocking {
+        (
+            quote! {(|| { #run })()},
+            quote! {#[allow(clippy::redundant_closure_call)]},
+        )
+    } else {
+        (quote! {async { #run }.await}, quote! {})
+    };
+
+    quote! {
+        #allow
+        pub #asyncness fn #name(&self, #inputs) #output {
+            let body = match #run {
+                Ok(body) => body,
+                #error_decoder
+                Err(err) => return Err(::core::convert::From::from(err)),
+            };
+            Ok(body)
+        }
+    }
+}
+
+/// What a method returns in `Ok`
+enum ResponseKind {
+    /// The deserialized body
+    Body,
+    /// `feign::Response<T>`, the deserialized body with the status and headers
+    Response,
+    /// `feign::RawResponse`, the body is not deserialized
+    Raw,
+    /// `feign::ByteStream`, the body is streamed
+    Stream,
+    /// `feign::JsonLines<T>`, the body is streamed and every line deserialized
+    Lines,
+    /// `impl AsyncRead`, the body is streamed
+    Read,
+    /// `feign::EventStream<T>`, server-sent events
+    Events,
+}
+
+/// Last segment of a type path, `ClientResult` of `feign::ClientResult<T>`
+fn last_segment(ty: &syn::Type) -> Option<&syn::PathSegment> {
+    match ty {
+        syn::Type::Path(path) => path.path.segments.last(),
+        _ => None,
+    }
+}
+
+/// `T` of `ClientResult<T>` or `Result<T, E>`
+fn ok_type(output: &syn::ReturnType) -> Option<&syn::Type> {
+    match output {
+        syn::ReturnType::Type(_, ty) => first_type_argument(ty),
+        syn::ReturnType::Default => None,
+    }
+}
+
+/// `T` of `Type<T, ...>`
+fn first_type_argument(ty: &syn::Type) -> Option<&syn::Type> {
+    match &last_segment(ty)?.arguments {
+        syn::PathArguments::AngleBracketed(args) => args.args.iter().find_map(|arg| match arg {
+            syn::GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        }),
+        _ => None,
+    }
+}
+
+fn response_kind(output: &syn::ReturnType) -> ResponseKind {
+    if let Some(syn::Type::ImplTrait(ty)) = ok_type(output) {
+        let read = ty.bounds.iter().any(|bound| {
+            matches!(bound, syn::TypeParamBound::Trait(bound)
+                if bound.path.segments.last().is_some_and(|segment| segment.ident == "AsyncRead"))
+        });
+        if read {
+            return ResponseKind::Read;
+        }
+    }
+    match ok_type(output)
+        .and_then(feign_type)
+        .map(|segment| segment.ident.to_string())
+        .as_deref()
+    {
+        Some("Response") => ResponseKind::Response,
+        Some("RawResponse") => ResponseKind::Raw,
+        Some("ByteStream") => ResponseKind::Stream,
+        Some("JsonLines") => ResponseKind::Lines,
+        Some("EventStream") => ResponseKind::Events,
+        _ => ResponseKind::Body,
+    }
+}
+
+/// `Response` of `feign::Response<T>` or `::feign::Response<T>`, other paths are types of the user
+fn feign_type(ty: &syn::Type) -> Option<&syn::PathSegment> {
+    match ty {
+        syn::Type::Path(ty) if ty.qself.is_none() && ty.path.segments.len() == 2 => {
+            let mut segments = ty.path.segments.iter();
+            match (segments.next(), segments.next()) {
+                (Some(krate), last) if krate.ident == "feign" => last,
+                _ => None,
+            }
+        }
+        _ => None,
     }
 }
 
@@ -401,8 +1332,12 @@ fn http_method_to_token(method: HttpMethod) -> proc_macro2::TokenStream {
 
 /// body types
 enum RequestBody<'a> {
-    Form(&'a Box<syn::Pat>),
-    Json(&'a Box<syn::Pat>),
+    Form(&'a syn::Pat),
+    Json(&'a syn::Pat),
+    /// `#[multipart]` form the `#[part]` arguments are added to
+    Multipart(Option<&'a syn::Pat>),
+    /// `#[body]` sent as it is, or streamed
+    Raw(&'a syn::Ident, BodyArg),
 }
 
 /// Args of client
@@ -416,6 +1351,22 @@ struct ClientArgs {
     pub client_builder: Option<String>,
     #[darling(default)]
     pub before_send: Option<String>,
+    #[darling(default)]
+    pub after_receive: Option<String>,
+    #[darling(default)]
+    pub retry: Option<String>,
+    #[darling(default)]
+    pub timeout: Option<DurationArg>,
+    #[darling(default)]
+    pub connect_timeout: Option<DurationArg>,
+    #[darling(default)]
+    pub headers: Option<StaticHeaders>,
+    #[darling(default)]
+    pub codec: Option<CodecArg>,
+    #[darling(default)]
+    pub keep_trait: bool,
+    #[darling(default)]
+    pub mock: bool,
 }
 
 /// Args of request
@@ -423,7 +1374,382 @@ struct ClientArgs {
 struct Request {
     pub path: String,
     #[darling(default)]
-    pub deserialize: Option<String>,
+    pub deserialize: Option<DeserializeArg>,
+    #[darling(default)]
+    pub error_decoder: Option<String>,
+    #[darling(default)]
+    pub headers: Option<StaticHeaders>,
+    #[darling(default)]
+    pub codec: Option<CodecArg>,
+    #[darling(default)]
+    pub retry: Option<String>,
+    #[darling(default)]
+    pub timeout: Option<DurationArg>,
+}
+
+/// `headers("Name" = "value", ...)`, checked at compile time
+#[derive(Debug)]
+struct StaticHeaders(Vec<(String, String)>);
+
+impl FromMeta for StaticHeaders {
+    fn from_meta(item: &syn::Meta) -> darling::Result<Self> {
+        let list = match item {
+            syn::Meta::List(list) => list,
+            _ => return Err(Error::custom("expects headers(\"Name\" = \"value\")").with_span(item)),
+        };
+        let pairs = list
+            .parse_args_with(
+                syn::punctuated::Punctuated::<StaticHeader, syn::Token![,]>::parse_terminated,
+            )
+            .map_err(Error::from)?;
+        let mut headers = vec![];
+        for pair in pairs {
+            check_header_name(&pair.name.value())
+                .map_err(|err| Error::custom(err).with_span(&pair.name))?;
+            check_header_value(&pair.value.value())
+                .map_err(|err| Error::custom(err).with_span(&pair.value))?;
+            headers.push((pair.name.value(), pair.value.value()));
+        }
+        Ok(StaticHeaders(headers))
+    }
+}
+
+/// `"Name" = "value"`
+struct StaticHeader {
+    name: syn::LitStr,
+    value: syn::LitStr,
+}
+
+impl syn::parse::Parse for StaticHeader {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let name = input.parse()?;
+        input.parse::<syn::Token![=]>()?;
+        let value = input.parse()?;
+        Ok(StaticHeader { name, value })
+    }
+}
+
+/// Header names are tokens of RFC 9110
+fn check_header_name(name: &str) -> Result<(), String> {
+    let token = |c: char| c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c);
+    if name.is_empty() || !name.chars().all(token) {
+        return Err(format!("invalid header name {:?}", name));
+    }
+    Ok(())
+}
+
+/// Header values are visible ASCII, spaces and tabs
+fn check_header_value(value: &str) -> Result<(), String> {
+    if !value.chars().all(|c| c == '\t' || (' '..='~').contains(&c)) {
+        return Err(format!("invalid header value {:?}", value));
+    }
+    Ok(())
+}
+
+/// Name of a `#[header("Name")]` argument
+fn header_arg_name(attr: &syn::Attribute) -> String {
+    let name = match &attr.meta {
+        syn::Meta::List(list) => match list.parse_args::<syn::LitStr>() {
+            Ok(name) => name,
+            Err(_) => abort!(&list.span(), "expects #[header(\"Name\")]"),
+        },
+        _ => abort!(&attr.span(), "expects #[header(\"Name\")]"),
+    };
+    if let Err(err) = check_header_name(&name.value()) {
+        abort!(&name.span(), err);
+    }
+    name.value()
+}
+
+/// `deserialize = path::to::decoder` implementing `feign::Decoder`,
+/// or `deserialize = "path::to::function"` called as `function(&bytes).await`
+#[derive(Debug)]
+enum DeserializeArg {
+    Decoder(syn::Expr),
+    Function(String),
+}
+
+impl FromMeta for DeserializeArg {
+    fn from_expr(expr: &syn::Expr) -> darling::Result<Self> {
+        match expr {
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(lit),
+                ..
+            }) => Ok(DeserializeArg::Function(lit.value())),
+            expr => Ok(DeserializeArg::Decoder(expr.clone())),
+        }
+    }
+}
+
+/// `codec = feign::codec::Xml`, or the expression in a string
+#[derive(Debug)]
+struct CodecArg(syn::Expr);
+
+impl FromMeta for CodecArg {
+    fn from_expr(expr: &syn::Expr) -> darling::Result<Self> {
+        match expr {
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(lit),
+                ..
+            }) => lit.parse().map(CodecArg).map_err(darling::Error::from),
+            expr => Ok(CodecArg(expr.clone())),
+        }
+    }
+}
+
+/// Options of a `#[body]` argument
+#[derive(Debug, Default, FromMeta)]
+struct BodyArg {
+    #[darling(default)]
+    content_type: Option<String>,
+    /// Send a `Stream` of bytes, implied by an `impl Stream` argument
+    #[darling(default)]
+    stream: bool,
+    /// Send bytes or text as they are, implied by the types of bytes and text
+    #[darling(default)]
+    raw: bool,
+}
+
+impl BodyArg {
+    fn from_attr(attr: &syn::Attribute) -> Self {
+        match &attr.meta {
+            syn::Meta::Path(_) => Self::default(),
+            meta => match Self::from_meta(meta) {
+                Ok(arg) => arg,
+                Err(err) => abort!(&attr.span(), err.to_string()),
+            },
+        }
+    }
+}
+
+/// Bytes or text, `Bytes`, `Vec<u8>`, `String`, `&[u8]` or `&str`
+fn is_raw_type(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Reference(ty) => match &*ty.elem {
+            syn::Type::Slice(slice) => {
+                matches!(&*slice.elem, syn::Type::Path(elem) if elem.path.is_ident("u8"))
+            }
+            syn::Type::Path(elem) => elem.path.is_ident("str"),
+            _ => false,
+        },
+        syn::Type::Path(_) => match last_segment(ty) {
+            Some(segment) if segment.ident == "Vec" => match &segment.arguments {
+                syn::PathArguments::AngleBracketed(args) => matches!(
+                    args.args.first(),
+                    Some(syn::GenericArgument::Type(syn::Type::Path(elem))) if elem.path.is_ident("u8")
+                ),
+                _ => false,
+            },
+            Some(segment) => segment.ident == "Bytes" || segment.ident == "String",
+            None => false,
+        },
+        _ => false,
+    }
+}
+
+/// `impl Stream<...>`
+fn is_impl_stream(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::ImplTrait(ty) => ty.bounds.iter().any(|bound| {
+            matches!(bound, syn::TypeParamBound::Trait(bound)
+                if bound.path.segments.last().is_some_and(|segment| segment.ident == "Stream"))
+        }),
+        _ => false,
+    }
+}
+
+/// Options of a `#[part]` argument
+#[derive(Debug, Default, FromMeta)]
+struct PartArg {
+    /// Name of the part, defaults to the argument name
+    #[darling(default)]
+    name: Option<String>,
+    #[darling(default)]
+    filename: Option<String>,
+    #[darling(default)]
+    content_type: Option<String>,
+}
+
+impl PartArg {
+    fn from_attr(attr: &syn::Attribute) -> Self {
+        match &attr.meta {
+            syn::Meta::Path(_) => Self::default(),
+            meta => match Self::from_meta(meta) {
+                Ok(arg) => arg,
+                Err(err) => abort!(&attr.span(), err.to_string()),
+            },
+        }
+    }
+}
+
+/// Options of a `#[query]` argument
+#[derive(Debug, Default, FromMeta)]
+struct QueryArg {
+    /// Name of the pair, defaults to the argument name
+    #[darling(default)]
+    name: Option<String>,
+    /// Join the items of a sequence into one value, instead of repeating the name
+    #[darling(default)]
+    join: Option<String>,
+    /// Add the fields of a struct or a map
+    #[darling(default)]
+    flatten: bool,
+    /// A `(name, value)` tuple, added as one pair
+    #[darling(skip)]
+    pair: bool,
+}
+
+impl QueryArg {
+    fn from_attr(attr: &syn::Attribute, ty: &syn::Type) -> Self {
+        let mut arg = match &attr.meta {
+            syn::Meta::Path(_) => Self::default(),
+            meta => match Self::from_meta(meta) {
+                Ok(arg) => arg,
+                Err(err) => abort!(&attr.span(), err.to_string()),
+            },
+        };
+        if arg.flatten && arg.name.is_some() {
+            abort!(&attr.span(), "a flattened query has no name");
+        }
+        match tuple_len(ty) {
+            Some(2) if arg.name.is_some() || arg.join.is_some() || arg.flatten => abort!(
+                &attr.span(),
+                "a (name, value) query pair has no options"
+            ),
+            Some(2) => arg.pair = true,
+            Some(_) => abort!(
+                &ty.span(),
+                "a tuple query must be a (name, value) pair, use #[query(flatten)] with a sequence of pairs"
+            ),
+            None => {}
+        }
+        arg
+    }
+}
+
+/// Number of elements of a tuple type, behind references
+fn tuple_len(ty: &syn::Type) -> Option<usize> {
+    match ty {
+        syn::Type::Reference(ty) => tuple_len(&ty.elem),
+        syn::Type::Paren(ty) => tuple_len(&ty.elem),
+        syn::Type::Tuple(ty) => Some(ty.elems.len()),
+        _ => None,
+    }
+}
+
+/// A duration like `"500ms"`, `"5s"` or `"1m30s"`, parsed at compile time
+#[derive(Debug)]
+struct DurationArg {
+    millis: u64,
+    span: proc_macro2::Span,
+}
+
+impl FromMeta for DurationArg {
+    fn from_value(value: &syn::Lit) -> darling::Result<Self> {
+        match value {
+            syn::Lit::Str(lit) => match parse_duration(&lit.value()) {
+                Ok(millis) => Ok(DurationArg {
+                    millis,
+                    span: lit.span(),
+                }),
+                Err(err) => Err(Error::custom(err).with_span(lit)),
+            },
+            _ => Err(Error::unexpected_lit_type(value)),
+        }
+    }
+}
+
+/// Milliseconds of a duration made of numbers followed by `ms`, `s`, `m` or `h`
+fn parse_duration(value: &str) -> Result<u64, String> {
+    let invalid = || {
+        format!(
+            "invalid duration {:?}, expected a number followed by ms, s, m or h, like \"500ms\" or \"1m30s\"",
+            value
+        )
+    };
+    let mut rest = value.trim();
+    if rest.is_empty() {
+        return Err(invalid());
+    }
+    let mut millis: u64 = 0;
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .ok_or_else(invalid)?;
+        let number: u64 = rest[..digits].parse().map_err(|_| invalid())?;
+        rest = &rest[digits..];
+        let unit = rest
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let scale = match &rest[..unit] {
+            "ms" => 1,
+            "s" => 1000,
+            "m" => 60 * 1000,
+            "h" => 60 * 60 * 1000,
+            _ => return Err(invalid()),
+        };
+        millis = number
+            .checked_mul(scale)
+            .and_then(|part| millis.checked_add(part))
+            .ok_or_else(invalid)?;
+        rest = &rest[unit..];
+    }
+    Ok(millis)
+}
+
+/// Part of a request path template
+#[derive(Debug, PartialEq)]
+enum PathPart {
+    Literal(String),
+    /// `<name>`
+    Variable(String),
+}
+
+/// Split a request path like `/user/<id>/posts` into literals and variables
+fn parse_path_template(template: &str) -> Result<Vec<PathPart>, String> {
+    let mut parts = vec![];
+    let mut rest = template;
+    while let Some(open) = rest.find(['<', '>']) {
+        if rest[open..].starts_with('>') {
+            return Err(format!("unmatched `>` in path {:?}", template));
+        }
+        let close = rest[open + 1..]
+            .find(['<', '>'])
+            .filter(|close| rest[open + 1 + close..].starts_with('>'))
+            .ok_or_else(|| format!("unclosed `<` in path {:?}", template))?;
+        let name = &rest[open + 1..open + 1 + close];
+        if syn::parse_str::<syn::Ident>(name).is_err() {
+            return Err(format!(
+                "path variable <{}> in {:?} is not an identifier",
+                name, template
+            ));
+        }
+        if open > 0 {
+            parts.push(PathPart::Literal(rest[..open].to_string()));
+        }
+        parts.push(PathPart::Variable(name.to_string()));
+        rest = &rest[open + close + 2..];
+    }
+    if !rest.is_empty() {
+        parts.push(PathPart::Literal(rest.to_string()));
+    }
+    Ok(parts)
+}
+
+/// `#[path]` is percent-encoded, `#[path(raw)]` is inserted as is, same for `#[feign_path]`
+fn is_raw_path(attr: &syn::Attribute) -> bool {
+    let expects = || {
+        let name = attr.path().get_ident().unwrap();
+        format!("expects #[{0}] or #[{0}(raw)]", name)
+    };
+    match &attr.meta {
+        syn::Meta::Path(_) => false,
+        syn::Meta::List(list) => match list.parse_args::<syn::Ident>() {
+            Ok(ident) if ident == "raw" => true,
+            _ => abort!(&list.span(), expects()),
+        },
+        syn::Meta::NameValue(meta) => abort!(&meta.span(), expects()),
+    }
 }
 
 /// Derive macro for the `Args` trait
@@ -466,7 +1792,7 @@ pub fn derive_args(input: TokenStream) -> TokenStream {
         ),
     };
 
-    let mut path_fields: Vec<(&syn::Ident, &syn::Type)> = Vec::new();
+    let mut path_fields: Vec<(&syn::Ident, bool)> = Vec::new();
     let mut query_fields: Vec<(&syn::Ident, &syn::Type)> = Vec::new();
     let mut json_field: Option<(&syn::Ident, &syn::Type)> = None;
     let mut form_field: Option<(&syn::Ident, &syn::Type)> = None;
@@ -477,16 +1803,19 @@ pub fn derive_args(input: TokenStream) -> TokenStream {
         let field_type = &field.ty;
 
         let mut has_path = false;
+        let mut raw_path = false;
         let mut has_query = false;
         let mut has_json = false;
         let mut has_form = false;
         let mut has_headers = false;
 
         for attr in &field.attrs {
-            if let syn::Meta::Path(path) = &attr.meta {
+            if attr.path().is_ident("feign_path") {
+                has_path = true;
+                raw_path = is_raw_path(attr);
+            } else if let syn::Meta::Path(path) = &attr.meta {
                 if let Some(ident) = path.get_ident() {
                     match ident.to_string().as_str() {
-                        "feign_path" => has_path = true,
                         "feign_query" => has_query = true,
                         "feign_json" => has_json = true,
                         "feign_form" => has_form = true,
@@ -498,7 +1827,7 @@ pub fn derive_args(input: TokenStream) -> TokenStream {
         }
 
         if has_path {
-            path_fields.push((field_name, field_type));
+            path_fields.push((field_name, raw_path));
         } else if has_query {
             query_fields.push((field_name, field_type));
         } else if has_json {
@@ -525,14 +1854,20 @@ pub fn derive_args(input: TokenStream) -> TokenStream {
 
     // Generate request_path method
     let path = if path_fields.is_empty() {
-        quote! {}
+        quote! {vec![]}
     } else {
         let path_pairs: Vec<_> = path_fields
             .iter()
-            .map(|(field_name, _)| {
+            .map(|(field_name, raw)| {
                 let id = format!("<{}>", field_name);
-                quote! {
-                    (#id, format!("{}", self.#field_name))
+                if *raw {
+                    quote! {
+                        (#id, format!("{}", self.#field_name))
+                    }
+                } else {
+                    quote! {
+                        (#id, ::feign::encode_path(&self.#field_name))
+                    }
                 }
             })
             .collect();
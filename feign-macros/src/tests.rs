@@ -0,0 +1,52 @@
+use crate::{check_header_name, check_header_value, parse_duration, parse_path_template};
+
+#[test]
+fn test_parse_duration() {
+    assert_eq!(parse_duration("500ms"), Ok(500));
+    assert_eq!(parse_duration("5s"), Ok(5000));
+    assert_eq!(parse_duration("1m30s"), Ok(90_000));
+    assert_eq!(parse_duration("2h"), Ok(7_200_000));
+    assert!(parse_duration("").is_err());
+    assert!(parse_duration("5").is_err());
+    assert!(parse_duration("s").is_err());
+    assert!(parse_duration("5sec").is_err());
+    assert!(parse_duration("1.5s").is_err());
+}
+
+#[test]
+fn test_parse_path_template() {
+    use crate::PathPart::{Literal, Variable};
+
+    assert_eq!(parse_path_template(""), Ok(vec![]));
+    assert_eq!(
+        parse_path_template("/find_by_id/<id>"),
+        Ok(vec![Literal("/find_by_id/".into()), Variable("id".into())])
+    );
+    assert_eq!(
+        parse_path_template("/<a>/<b>.json"),
+        Ok(vec![
+            Literal("/".into()),
+            Variable("a".into()),
+            Literal("/".into()),
+            Variable("b".into()),
+            Literal(".json".into()),
+        ])
+    );
+    assert!(parse_path_template("/<id").is_err());
+    assert!(parse_path_template("/id>").is_err());
+    assert!(parse_path_template("/<>").is_err());
+    assert!(parse_path_template("/<a<b>>").is_err());
+    assert!(parse_path_template("/<a b>").is_err());
+}
+
+#[test]
+fn test_check_header() {
+    assert!(check_header_name("X-Request-Id").is_ok());
+    assert!(check_header_name("").is_err());
+    assert!(check_header_name("X Request").is_err());
+    assert!(check_header_name("X:Request").is_err());
+    assert!(check_header_value("svc/1.0 (linux)").is_ok());
+    assert!(check_header_value("").is_ok());
+    assert!(check_header_value("a\r\nb").is_err());
+    assert!(check_header_value("ü").is_err());
+}
@@ -0,0 +1,526 @@
+//! Load balancing between servers.
+//!
+//! A [`LoadBalancer`] is a [`Host`] choosing one of its [`Endpoint`]s with a [`Strategy`]
+//! for every request. Selection is lock-free, and with an [`Ejection`] the outcomes of
+//! the requests eject failing endpoints for a cool-down period.
+//!
+//! ```ignore
+//! use feign::balance::{Ejection, LoadBalancer, WeightedRoundRobin};
+//!
+//! let hosts = LoadBalancer::weighted(
+//!     WeightedRoundRobin::new(),
+//!     vec![
+//!         ("http://127.0.0.1:3031".to_string(), 3),
+//!         ("http://127.0.0.1:3032".to_string(), 1),
+//!     ],
+//! )
+//! .unwrap()
+//! .with_ejection(Ejection::default());
+//!
+//! let user_client = UserClient::builder().with_host(hosts).build();
+//! ```
+
+use std::collections::hash_map::{DefaultHasher, RandomState};
+use std::fmt::{Debug, Display, Formatter};
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::sync::atomic::{AtomicU32, AtomicU64, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::{ClientResult, Host, HttpMethod, Outcome, RequestContext};
+
+/// A server behind a [`LoadBalancer`]
+#[derive(Debug)]
+pub struct Endpoint {
+    url: String,
+    weight: u32,
+    in_flight: AtomicUsize,
+    failures: AtomicU32,
+    ejected_until: AtomicU64,
+}
+
+impl Endpoint {
+    fn new(url: String, weight: u32) -> Self {
+        Self {
+            url,
+            weight,
+            in_flight: AtomicUsize::new(0),
+            failures: AtomicU32::new(0),
+            ejected_until: AtomicU64::new(0),
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        self.url.as_str()
+    }
+
+    pub fn weight(&self) -> u32 {
+        self.weight
+    }
+
+    /// Requests selected this endpoint and not finished yet
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::Relaxed)
+    }
+}
+
+/// Picks an endpoint for every request
+pub trait Strategy: Debug + Send + Sync + 'static {
+    /// Called once with all endpoints when the load balancer is created.
+    fn prepare(&mut self, endpoints: &[Endpoint]) {
+        let _ = endpoints;
+    }
+
+    /// Index of the endpoint to use, endpoints for which `available` is false are ejected.
+    fn pick(
+        &self,
+        endpoints: &[Endpoint],
+        available: &dyn Fn(usize) -> bool,
+        request: &RequestContext,
+    ) -> usize;
+}
+
+/// Eject an endpoint for `cool_down` after `failures` consecutive failures.
+///
+/// When all endpoints are ejected they are all used again.
+#[derive(Debug, Clone, Copy)]
+pub struct Ejection {
+    pub failures: u32,
+    pub cool_down: Duration,
+}
+
+impl Ejection {
+    pub fn new(failures: u32, cool_down: Duration) -> Self {
+        Self {
+            failures,
+            cool_down,
+        }
+    }
+}
+
+impl Default for Ejection {
+    fn default() -> Self {
+        Self::new(5, Duration::from_secs(30))
+    }
+}
+
+/// Largest weight of an endpoint, the weighted round-robin schedule and the hash ring grow with the weights
+pub const MAX_WEIGHT: u32 = 1000;
+
+/// Largest number of virtual nodes of an endpoint of weight 1 in a [`ConsistentHash`] ring
+pub const MAX_REPLICAS: u32 = 1000;
+
+/// A [`Host`] balancing the requests between endpoints
+pub struct LoadBalancer<S = RoundRobin> {
+    endpoints: Vec<Endpoint>,
+    strategy: S,
+    ejection: Option<Ejection>,
+    epoch: Instant,
+}
+
+impl<S: Strategy> LoadBalancer<S> {
+    pub fn new(strategy: S, hosts: Vec<String>) -> ClientResult<Self> {
+        Self::weighted(strategy, hosts.into_iter().map(|host| (host, 1)).collect())
+    }
+
+    /// Endpoints with weights from 1 to [`MAX_WEIGHT`], used by the weighted strategies.
+    pub fn weighted(mut strategy: S, hosts: Vec<(String, u32)>) -> ClientResult<Self> {
+        if hosts.is_empty() {
+            return Err(anyhow::anyhow!("LoadBalancer hosts is empty"));
+        }
+        if let Some((host, _)) = hosts.iter().find(|(_, weight)| *weight == 0) {
+            return Err(anyhow::anyhow!("LoadBalancer weight of {} is zero", host));
+        }
+        if let Some((host, weight)) = hosts.iter().find(|(_, weight)| *weight > MAX_WEIGHT) {
+            return Err(anyhow::anyhow!(
+                "LoadBalancer weight of {} is {}, more than {}",
+                host,
+                weight,
+                MAX_WEIGHT
+            ));
+        }
+        let endpoints: Vec<Endpoint> = hosts
+            .into_iter()
+            .map(|(url, weight)| Endpoint::new(url, weight))
+            .collect();
+        strategy.prepare(&endpoints);
+        Ok(Self {
+            endpoints,
+            strategy,
+            ejection: None,
+            epoch: Instant::now(),
+        })
+    }
+
+    pub fn with_ejection(mut self, ejection: Ejection) -> Self {
+        self.ejection = Some(ejection);
+        self
+    }
+
+    pub fn endpoints(&self) -> &[Endpoint] {
+        &self.endpoints
+    }
+
+    /// Milliseconds since the balancer was created, starts at 1 so 0 is never ejected.
+    fn now(&self) -> u64 {
+        self.epoch.elapsed().as_millis() as u64 + 1
+    }
+
+    fn pick(&self, request: &RequestContext) -> &Endpoint {
+        let now = self.now();
+        let ejected =
+            |index: usize| self.endpoints[index].ejected_until.load(Ordering::Relaxed) > now;
+        let index = if self.ejection.is_some() && (0..self.endpoints.len()).any(|i| !ejected(i)) {
+            self.strategy
+                .pick(&self.endpoints, &|index| !ejected(index), request)
+        } else {
+            self.strategy.pick(&self.endpoints, &|_| true, request)
+        };
+        &self.endpoints[index % self.endpoints.len()]
+    }
+}
+
+impl<S> Display for LoadBalancer<S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.endpoints.iter().map(|endpoint| &endpoint.url))
+            .finish()
+    }
+}
+
+impl<S: Debug> Debug for LoadBalancer<S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LoadBalancer")
+            .field("endpoints", &self.endpoints)
+            .field("strategy", &self.strategy)
+            .field("ejection", &self.ejection)
+            .finish()
+    }
+}
+
+impl<S: Strategy> Host for LoadBalancer<S> {
+    fn host(&self) -> &str {
+        self.pick(&RequestContext {
+            method: HttpMethod::Get,
+            path: "",
+        })
+        .url()
+    }
+
+    fn select(&self, request: &RequestContext) -> &str {
+        let endpoint = self.pick(request);
+        endpoint.in_flight.fetch_add(1, Ordering::Relaxed);
+        endpoint.url()
+    }
+
+    fn feedback(&self, host: &str, outcome: Outcome) {
+        let endpoint = match self.endpoints.iter().find(|endpoint| endpoint.url == host) {
+            Some(endpoint) => endpoint,
+            None => return,
+        };
+        let _ = endpoint
+            .in_flight
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |v| v.checked_sub(1));
+        match (outcome, self.ejection) {
+            (Outcome::Success, _) => endpoint.failures.store(0, Ordering::Relaxed),
+            (Outcome::Failure, Some(ejection)) => {
+                let failures = endpoint.failures.fetch_add(1, Ordering::Relaxed) + 1;
+                if failures >= ejection.failures {
+                    endpoint.failures.store(0, Ordering::Relaxed);
+                    endpoint.ejected_until.store(
+                        self.now() + ejection.cool_down.as_millis() as u64,
+                        Ordering::Relaxed,
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// First available endpoint at or after `start`, or `start` when none is available
+fn next_available(len: usize, start: usize, available: &dyn Fn(usize) -> bool) -> usize {
+    (0..len)
+        .map(|offset| (start + offset) % len)
+        .find(|index| available(*index))
+        .unwrap_or(start % len)
+}
+
+/// Endpoints in turn, ignoring the weights
+#[derive(Debug, Default)]
+pub struct RoundRobin {
+    next: AtomicUsize,
+}
+
+impl RoundRobin {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Strategy for RoundRobin {
+    fn pick(
+        &self,
+        endpoints: &[Endpoint],
+        available: &dyn Fn(usize) -> bool,
+        _request: &RequestContext,
+    ) -> usize {
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+        next_available(endpoints.len(), start, available)
+    }
+}
+
+/// Smooth weighted round-robin, an endpoint of weight 3 is used 3 times as often as one of weight 1,
+/// interleaved with the others.
+#[derive(Debug, Default)]
+pub struct WeightedRoundRobin {
+    schedule: Vec<usize>,
+    next: AtomicUsize,
+}
+
+impl WeightedRoundRobin {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Strategy for WeightedRoundRobin {
+    fn prepare(&mut self, endpoints: &[Endpoint]) {
+        let divisor = endpoints
+            .iter()
+            .map(|endpoint| endpoint.weight as u64)
+            .fold(0, gcd);
+        let weights: Vec<i64> = endpoints
+            .iter()
+            .map(|endpoint| (endpoint.weight as u64 / divisor) as i64)
+            .collect();
+        let total: i64 = weights.iter().sum();
+        let mut current = vec![0i64; weights.len()];
+        self.schedule = (0..total)
+            .map(|_| {
+                let mut best = 0;
+                for (index, weight) in weights.iter().enumerate() {
+                    current[index] += weight;
+                    if current[index] > current[best] {
+                        best = index;
+                    }
+                }
+                current[best] -= total;
+                best
+            })
+            .collect();
+    }
+
+    fn pick(
+        &self,
+        _endpoints: &[Endpoint],
+        available: &dyn Fn(usize) -> bool,
+        _request: &RequestContext,
+    ) -> usize {
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+        let len = self.schedule.len();
+        (0..len)
+            .map(|offset| self.schedule[(start + offset) % len])
+            .find(|index| available(*index))
+            .unwrap_or(self.schedule[start % len])
+    }
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Random endpoint, with a probability proportional to its weight
+#[derive(Debug)]
+pub struct Random {
+    state: AtomicU64,
+}
+
+impl Random {
+    pub fn new() -> Self {
+        Self::with_seed(RandomState::new().build_hasher().finish())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            state: AtomicU64::new(seed),
+        }
+    }
+
+    /// splitmix64
+    fn next_u64(&self) -> u64 {
+        let mut z = self
+            .state
+            .fetch_add(0x9E3779B97F4A7C15, Ordering::Relaxed)
+            .wrapping_add(0x9E3779B97F4A7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+}
+
+impl Default for Random {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Strategy for Random {
+    fn pick(
+        &self,
+        endpoints: &[Endpoint],
+        available: &dyn Fn(usize) -> bool,
+        _request: &RequestContext,
+    ) -> usize {
+        let total: u64 = (0..endpoints.len())
+            .filter(|index| available(*index))
+            .map(|index| endpoints[index].weight as u64)
+            .sum();
+        if total == 0 {
+            return (self.next_u64() % endpoints.len() as u64) as usize;
+        }
+        let mut point = self.next_u64() % total;
+        for (index, endpoint) in endpoints.iter().enumerate() {
+            if !available(index) {
+                continue;
+            }
+            if point < endpoint.weight as u64 {
+                return index;
+            }
+            point -= endpoint.weight as u64;
+        }
+        0
+    }
+}
+
+/// Endpoint with the fewest in-flight requests relative to its weight,
+/// ties are broken in turn.
+#[derive(Debug, Default)]
+pub struct LeastInFlight {
+    next: AtomicUsize,
+}
+
+impl LeastInFlight {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Strategy for LeastInFlight {
+    fn pick(
+        &self,
+        endpoints: &[Endpoint],
+        available: &dyn Fn(usize) -> bool,
+        _request: &RequestContext,
+    ) -> usize {
+        let len = endpoints.len();
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+        let mut best: Option<(usize, u64)> = None;
+        for index in (0..len).map(|offset| (start + offset) % len) {
+            if !available(index) {
+                continue;
+            }
+            let load = endpoints[index].in_flight() as u64;
+            let better = match best {
+                None => true,
+                Some((best, best_load)) => {
+                    load * (endpoints[best].weight as u64)
+                        < best_load * (endpoints[index].weight as u64)
+                }
+            };
+            if better {
+                best = Some((index, load));
+            }
+        }
+        best.map(|(index, _)| index).unwrap_or(start % len)
+    }
+}
+
+/// Consistent hashing on a ring of virtual nodes, requests with the same key go to the same
+/// endpoint while it is available, and only the keys of an ejected endpoint move.
+pub struct ConsistentHash {
+    key: Box<dyn Fn(&RequestContext) -> u64 + Send + Sync>,
+    replicas: u32,
+    ring: Vec<(u64, usize)>,
+}
+
+impl ConsistentHash {
+    /// Keyed by the request path
+    pub fn new() -> Self {
+        Self::with_key(|request| request.path.to_string())
+    }
+
+    /// Keyed by an attribute of the request
+    ///
+    /// ```ignore
+    /// ConsistentHash::with_key(|request| request.path.split('/').nth(1).map(str::to_owned))
+    /// ```
+    pub fn with_key<K: Hash>(key: impl Fn(&RequestContext) -> K + Send + Sync + 'static) -> Self {
+        Self {
+            key: Box::new(move |request| hash(&key(request))),
+            replicas: 160,
+            ring: vec![],
+        }
+    }
+
+    /// Virtual nodes of an endpoint of weight 1, defaults to 160, from 1 to [`MAX_REPLICAS`].
+    ///
+    /// An endpoint has `replicas * weight` nodes, the ring is allocated when the balancer is built.
+    pub fn with_replicas(mut self, replicas: u32) -> Self {
+        self.replicas = replicas.clamp(1, MAX_REPLICAS);
+        self
+    }
+}
+
+impl Default for ConsistentHash {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for ConsistentHash {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConsistentHash")
+            .field("replicas", &self.replicas)
+            .field("ring", &self.ring.len())
+            .finish()
+    }
+}
+
+impl Strategy for ConsistentHash {
+    fn prepare(&mut self, endpoints: &[Endpoint]) {
+        self.ring = endpoints
+            .iter()
+            .enumerate()
+            .flat_map(|(index, endpoint)| {
+                (0..self.replicas.saturating_mul(endpoint.weight))
+                    .map(move |replica| (hash(&(&endpoint.url, replica)), index))
+            })
+            .collect();
+        self.ring.sort_unstable();
+    }
+
+    fn pick(
+        &self,
+        _endpoints: &[Endpoint],
+        available: &dyn Fn(usize) -> bool,
+        request: &RequestContext,
+    ) -> usize {
+        let key = (self.key)(request);
+        let len = self.ring.len();
+        let start = self.ring.partition_point(|(point, _)| *point < key);
+        (0..len)
+            .map(|offset| self.ring[(start + offset) % len].1)
+            .find(|index| available(*index))
+            .unwrap_or(self.ring[start % len].1)
+    }
+}
+
+fn hash<T: Hash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
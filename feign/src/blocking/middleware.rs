@@ -0,0 +1,96 @@
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+use reqwest::blocking::{Client, Request, RequestBuilder, Response};
+
+use super::transport::send_http;
+use super::Transport;
+use crate::Error;
+
+/// A layer around sending the requests of a blocking client, see [`crate::Middleware`]
+pub trait Middleware: Send + Sync + 'static {
+    fn handle(&self, request: Request, next: Next<'_>) -> Result<Response, Error>;
+}
+
+/// The layers after the current one, copy it to send a request more than once
+#[derive(Clone, Copy)]
+pub struct Next<'a> {
+    client: &'a Client,
+    transport: Option<&'a dyn Transport>,
+    middlewares: &'a [Arc<dyn Middleware>],
+}
+
+impl<'a> Next<'a> {
+    pub fn new(
+        client: &'a Client,
+        transport: Option<&'a dyn Transport>,
+        middlewares: &'a [Arc<dyn Middleware>],
+    ) -> Self {
+        Self {
+            client,
+            transport,
+            middlewares,
+        }
+    }
+
+    /// Client sending the request after the last layer
+    pub fn client(&self) -> &'a Client {
+        self.client
+    }
+
+    pub fn run(self, request: Request) -> Result<Response, Error> {
+        match self.middlewares.split_first() {
+            Some((middleware, middlewares)) => {
+                middleware.handle(request, Next::new(self.client, self.transport, middlewares))
+            }
+            None => match self.transport {
+                Some(transport) => send_http(request, transport),
+                None => self.client.execute(request).map_err(Error::from),
+            },
+        }
+    }
+}
+
+/// Layers of a blocking client, in the order they were added, and the transport after them
+#[derive(Clone, Default)]
+pub struct Middlewares {
+    middlewares: Vec<Arc<dyn Middleware>>,
+    transport: Option<Arc<dyn Transport>>,
+}
+
+impl Middlewares {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, middleware: impl Middleware) {
+        self.middlewares.push(Arc::new(middleware));
+    }
+
+    /// Send with `transport` instead of the reqwest client
+    pub fn set_transport(&mut self, transport: impl Transport) {
+        self.transport = Some(Arc::new(transport));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.middlewares.is_empty()
+    }
+
+    /// Send the request through the layers, used by the generated methods
+    pub fn send(&self, request: RequestBuilder) -> Result<Response, Error> {
+        if self.middlewares.is_empty() && self.transport.is_none() {
+            return request.send().map_err(Error::from);
+        }
+        let (client, request) = request.build_split();
+        Next::new(&client, self.transport.as_deref(), &self.middlewares).run(request?)
+    }
+}
+
+impl Debug for Middlewares {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Middlewares")
+            .field("len", &self.middlewares.len())
+            .field("transport", &self.transport.is_some())
+            .finish()
+    }
+}
@@ -0,0 +1,65 @@
+use std::path::{Path, PathBuf};
+
+use bytes::Bytes;
+pub use reqwest::blocking::multipart::{Form, Part};
+
+use crate::Error;
+
+/// Value of a `#[part]` argument of a blocking client, see [`crate::multipart::IntoPart`]
+pub trait IntoPart {
+    fn into_part(self) -> Result<Part, Error>;
+}
+
+impl IntoPart for Part {
+    fn into_part(self) -> Result<Part, Error> {
+        Ok(self)
+    }
+}
+
+impl IntoPart for Vec<u8> {
+    fn into_part(self) -> Result<Part, Error> {
+        Ok(Part::bytes(self))
+    }
+}
+
+impl IntoPart for &[u8] {
+    fn into_part(self) -> Result<Part, Error> {
+        Ok(Part::bytes(self.to_vec()))
+    }
+}
+
+impl IntoPart for Bytes {
+    fn into_part(self) -> Result<Part, Error> {
+        Ok(Part::bytes(Vec::from(self)))
+    }
+}
+
+impl IntoPart for String {
+    fn into_part(self) -> Result<Part, Error> {
+        Ok(Part::text(self))
+    }
+}
+
+impl IntoPart for &str {
+    fn into_part(self) -> Result<Part, Error> {
+        Ok(Part::text(self.to_string()))
+    }
+}
+
+impl IntoPart for std::fs::File {
+    fn into_part(self) -> Result<Part, Error> {
+        Ok(Part::reader(self))
+    }
+}
+
+impl IntoPart for &Path {
+    fn into_part(self) -> Result<Part, Error> {
+        Part::file(self).map_err(Error::encode)
+    }
+}
+
+impl IntoPart for PathBuf {
+    fn into_part(self) -> Result<Part, Error> {
+        Part::file(self).map_err(Error::encode)
+    }
+}
@@ -0,0 +1,33 @@
+use bytes::Bytes;
+use reqwest::blocking::{Request, Response};
+
+use crate::{BoxError, Error};
+
+/// Sends the requests of a blocking client, see [`crate::Transport`]
+///
+/// The bodies are buffered, and the timeout of a method is left to the transport.
+pub trait Transport: Send + Sync + 'static {
+    fn send(&self, request: http::Request<Bytes>) -> Result<http::Response<Bytes>, BoxError>;
+}
+
+/// Send a reqwest request as an `http::Request` with the transport
+pub(crate) fn send_http(
+    mut request: Request,
+    transport: &dyn Transport,
+) -> Result<Response, Error> {
+    let body = match request.body_mut() {
+        Some(body) => Bytes::copy_from_slice(body.buffer()?),
+        None => Bytes::new(),
+    };
+    let mut http_request = http::Request::new(body);
+    *http_request.method_mut() = request.method().clone();
+    *http_request.uri_mut() = request
+        .url()
+        .as_str()
+        .parse()
+        .map_err(|error: http::uri::InvalidUri| Error::Encode(error.into()))?;
+    *http_request.version_mut() = request.version();
+    *http_request.headers_mut() = request.headers().clone();
+    let response = transport.send(http_request).map_err(Error::Transport)?;
+    Ok(Response::from(crate::with_url(response, request.url())))
+}
@@ -0,0 +1,69 @@
+use bytes::Bytes;
+
+/// Value of a `#[body]` argument, sent as it is
+///
+/// ```ignore
+/// #[post(path = "/notes")]
+/// async fn save(&self, #[body] note: String) -> ClientResult<()>;
+/// #[put(path = "/image")]
+/// async fn image(&self, #[body(content_type = "image/png")] image: Bytes) -> ClientResult<()>;
+/// ```
+pub trait IntoBody {
+    /// Sent when `#[body]` has no `content_type`
+    fn content_type(&self) -> &'static str;
+
+    fn into_body(self) -> Bytes;
+}
+
+const OCTET_STREAM: &str = "application/octet-stream";
+const TEXT: &str = "text/plain; charset=utf-8";
+
+impl IntoBody for Bytes {
+    fn content_type(&self) -> &'static str {
+        OCTET_STREAM
+    }
+
+    fn into_body(self) -> Bytes {
+        self
+    }
+}
+
+impl IntoBody for Vec<u8> {
+    fn content_type(&self) -> &'static str {
+        OCTET_STREAM
+    }
+
+    fn into_body(self) -> Bytes {
+        Bytes::from(self)
+    }
+}
+
+impl IntoBody for &[u8] {
+    fn content_type(&self) -> &'static str {
+        OCTET_STREAM
+    }
+
+    fn into_body(self) -> Bytes {
+        Bytes::copy_from_slice(self)
+    }
+}
+
+impl IntoBody for String {
+    fn content_type(&self) -> &'static str {
+        TEXT
+    }
+
+    fn into_body(self) -> Bytes {
+        Bytes::from(self)
+    }
+}
+
+impl IntoBody for &str {
+    fn content_type(&self) -> &'static str {
+        TEXT
+    }
+
+    fn into_body(self) -> Bytes {
+        Bytes::copy_from_slice(self.as_bytes())
+    }
+}
@@ -0,0 +1,210 @@
+//! Encoding of the request bodies and decoding of the responses
+//!
+//! Select a codec with `codec = ...` on `#[client]` or on a method, an expression of a type implementing [`Codec`],
+//! or the expression in a string.
+//! `#[body]` arguments that are not bytes or text are encoded with it, `Accept` is set to what it decodes,
+//! and the responses are decoded with it unless the method has a `deserialize` function.
+//!
+//! ```ignore
+//! #[client(host = "http://127.0.0.1:3000", path = "/user", codec = feign::codec::Xml)]
+//! pub trait UserClient {
+//!     #[post(path = "/new_user")]
+//!     async fn new_user(&self, #[body] user: &User) -> ClientResult<User>;
+//!     #[get(path = "/find_by_id/<id>", codec = feign::codec::Auto)]
+//!     async fn find_by_id(&self, #[path] id: i64) -> ClientResult<User>;
+//! }
+//! ```
+
+use std::sync::OnceLock;
+
+use bytes::Bytes;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::BoxError;
+
+pub trait Codec {
+    /// `Content-Type` of the encoded bodies
+    fn content_type(&self) -> &str;
+
+    /// `Accept` of the requests, defaults to the content type
+    fn accept(&self) -> &str {
+        self.content_type()
+    }
+
+    fn encode<T: Serialize + ?Sized>(&self, value: &T) -> Result<Bytes, BoxError>;
+
+    /// `content_type` is the `Content-Type` of the response
+    fn decode<T: DeserializeOwned>(
+        &self,
+        content_type: Option<&str>,
+        body: &[u8],
+    ) -> Result<T, BoxError>;
+}
+
+/// `application/json`, the default
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Json;
+
+impl Codec for Json {
+    fn content_type(&self) -> &str {
+        "application/json"
+    }
+
+    fn encode<T: Serialize + ?Sized>(&self, value: &T) -> Result<Bytes, BoxError> {
+        Ok(serde_json::to_vec(value)?.into())
+    }
+
+    fn decode<T: DeserializeOwned>(&self, _: Option<&str>, body: &[u8]) -> Result<T, BoxError> {
+        Ok(serde_json::from_slice(body)?)
+    }
+}
+
+/// `application/x-www-form-urlencoded`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Form;
+
+impl Codec for Form {
+    fn content_type(&self) -> &str {
+        "application/x-www-form-urlencoded"
+    }
+
+    fn encode<T: Serialize + ?Sized>(&self, value: &T) -> Result<Bytes, BoxError> {
+        Ok(serde_urlencoded::to_string(value)?.into())
+    }
+
+    fn decode<T: DeserializeOwned>(&self, _: Option<&str>, body: &[u8]) -> Result<T, BoxError> {
+        Ok(serde_urlencoded::from_bytes(body)?)
+    }
+}
+
+/// `application/xml`, enable the `xml` feature
+#[cfg(feature = "xml")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Xml;
+
+#[cfg(feature = "xml")]
+impl Codec for Xml {
+    fn content_type(&self) -> &str {
+        "application/xml"
+    }
+
+    fn accept(&self) -> &str {
+        "application/xml, text/xml"
+    }
+
+    fn encode<T: Serialize + ?Sized>(&self, value: &T) -> Result<Bytes, BoxError> {
+        Ok(quick_xml::se::to_string(value)?.into())
+    }
+
+    fn decode<T: DeserializeOwned>(&self, _: Option<&str>, body: &[u8]) -> Result<T, BoxError> {
+        Ok(quick_xml::de::from_str(std::str::from_utf8(body)?)?)
+    }
+}
+
+/// `application/msgpack`, enable the `msgpack` feature, structs are encoded as maps
+#[cfg(feature = "msgpack")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MsgPack;
+
+#[cfg(feature = "msgpack")]
+impl Codec for MsgPack {
+    fn content_type(&self) -> &str {
+        "application/msgpack"
+    }
+
+    fn accept(&self) -> &str {
+        "application/msgpack, application/x-msgpack"
+    }
+
+    fn encode<T: Serialize + ?Sized>(&self, value: &T) -> Result<Bytes, BoxError> {
+        Ok(rmp_serde::to_vec_named(value)?.into())
+    }
+
+    fn decode<T: DeserializeOwned>(&self, _: Option<&str>, body: &[u8]) -> Result<T, BoxError> {
+        Ok(rmp_serde::from_slice(body)?)
+    }
+}
+
+/// `application/cbor`, enable the `cbor` feature
+#[cfg(feature = "cbor")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Cbor;
+
+#[cfg(feature = "cbor")]
+impl Codec for Cbor {
+    fn content_type(&self) -> &str {
+        "application/cbor"
+    }
+
+    fn encode<T: Serialize + ?Sized>(&self, value: &T) -> Result<Bytes, BoxError> {
+        let mut body = vec![];
+        ciborium::into_writer(value, &mut body)?;
+        Ok(body.into())
+    }
+
+    fn decode<T: DeserializeOwned>(&self, _: Option<&str>, body: &[u8]) -> Result<T, BoxError> {
+        Ok(ciborium::from_reader(body)?)
+    }
+}
+
+/// Encodes JSON, accepts every enabled codec and decodes the response with the one of its `Content-Type`,
+/// JSON when it has none or an unknown one
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Auto;
+
+impl Codec for Auto {
+    fn content_type(&self) -> &str {
+        Json.content_type()
+    }
+
+    fn accept(&self) -> &str {
+        static ACCEPT: OnceLock<String> = OnceLock::new();
+        ACCEPT.get_or_init(|| {
+            // json is preferred
+            #[allow(unused_mut, clippy::useless_vec)]
+            let mut accept = vec!["application/json"];
+            #[cfg(feature = "xml")]
+            accept.push("application/xml;q=0.9");
+            #[cfg(feature = "msgpack")]
+            accept.push("application/msgpack;q=0.9");
+            #[cfg(feature = "cbor")]
+            accept.push("application/cbor;q=0.9");
+            accept.join(", ")
+        })
+    }
+
+    fn encode<T: Serialize + ?Sized>(&self, value: &T) -> Result<Bytes, BoxError> {
+        Json.encode(value)
+    }
+
+    fn decode<T: DeserializeOwned>(
+        &self,
+        content_type: Option<&str>,
+        body: &[u8],
+    ) -> Result<T, BoxError> {
+        let essence = content_type
+            .and_then(|content_type| content_type.split(';').next())
+            .map(|essence| essence.trim().to_ascii_lowercase())
+            .unwrap_or_default();
+        #[cfg(feature = "xml")]
+        if essence == "application/xml" || essence == "text/xml" || essence.ends_with("+xml") {
+            return Xml.decode(content_type, body);
+        }
+        #[cfg(feature = "msgpack")]
+        if matches!(
+            &*essence,
+            "application/msgpack" | "application/x-msgpack" | "application/vnd.msgpack"
+        ) {
+            return MsgPack.decode(content_type, body);
+        }
+        #[cfg(feature = "cbor")]
+        if essence == "application/cbor" {
+            return Cbor.decode(content_type, body);
+        }
+        if essence == "application/x-www-form-urlencoded" {
+            return Form.decode(content_type, body);
+        }
+        Json.decode(content_type, body)
+    }
+}
@@ -0,0 +1,49 @@
+use bytes::Bytes;
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+
+use crate::BoxError;
+
+/// The response given to a [`Decoder`]
+#[derive(Debug, Clone, Copy)]
+pub struct ResponseView<'a> {
+    pub status: StatusCode,
+    pub headers: &'a HeaderMap,
+    pub body: &'a Bytes,
+}
+
+impl ResponseView<'_> {
+    /// Value of a header, if it is present and visible ASCII
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name)?.to_str().ok()
+    }
+}
+
+/// Deserializes the responses of a method, set it with `deserialize = path::to::decoder`,
+/// an expression of a type implementing `Decoder<T>` for the returned `T`
+///
+/// ```ignore
+/// struct Version;
+///
+/// impl feign::Decoder<u32> for Version {
+///     fn decode(&self, response: feign::ResponseView<'_>) -> Result<u32, feign::BoxError> {
+///         Ok(response.header("x-version").ok_or("no version")?.parse()?)
+///     }
+/// }
+///
+/// #[get(path = "/version", deserialize = Version)]
+/// async fn version(&self) -> ClientResult<u32>;
+/// ```
+pub trait Decoder<T> {
+    fn decode(&self, response: ResponseView<'_>) -> Result<T, BoxError>;
+}
+
+/// The body as a string, invalid UTF-8 is replaced
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Text;
+
+impl Decoder<String> for Text {
+    fn decode(&self, response: ResponseView<'_>) -> Result<String, BoxError> {
+        Ok(String::from_utf8_lossy(response.body).into_owned())
+    }
+}
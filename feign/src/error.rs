@@ -0,0 +1,141 @@
+use std::fmt::{Display, Formatter};
+
+use bytes::Bytes;
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+
+/// Boxed error of the hooks, codecs and transports
+pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Error of the generated methods
+///
+/// It converts into `anyhow::Error` and `Box<dyn std::error::Error>`, so methods returning
+/// `ClientResult` keep working, declare `Result<T, feign::Error>` to match on the kind of failure.
+#[derive(Debug)]
+pub enum Error {
+    /// Connecting, sending the request or reading the response failed
+    Transport(BoxError),
+    /// The server responded with a 4xx or 5xx status
+    Status(Box<StatusError>),
+    /// The response body could not be deserialized
+    Decode { body: Bytes, source: BoxError },
+    /// The request could not be built, the body or the query failed to serialize
+    Encode(BoxError),
+    /// The client builder, before_send, after_receive or the state failed
+    Hook(BoxError),
+    /// The host, path and request path do not make an url
+    InvalidUrl {
+        url: String,
+        source: url::ParseError,
+    },
+}
+
+/// Response of [`Error::Status`], when its body can not be read the request fails with [`Error::Transport`]
+#[derive(Debug)]
+pub struct StatusError {
+    pub code: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Bytes,
+}
+
+impl Error {
+    pub fn hook(error: impl Into<BoxError>) -> Self {
+        Error::Hook(error.into())
+    }
+
+    pub fn encode(error: impl Into<BoxError>) -> Self {
+        Error::Encode(error.into())
+    }
+
+    pub fn decode(body: Bytes, error: impl Into<BoxError>) -> Self {
+        Error::Decode {
+            body,
+            source: error.into(),
+        }
+    }
+
+    /// Status of [`Error::Status`]
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            Error::Status(status) => Some(status.code),
+            _ => None,
+        }
+    }
+
+    /// Response body of [`Error::Status`] and [`Error::Decode`]
+    pub fn body(&self) -> Option<&Bytes> {
+        match self {
+            Error::Status(status) => Some(&status.body),
+            Error::Decode { body, .. } => Some(body),
+            _ => None,
+        }
+    }
+
+    /// Deserialize the json body of [`Error::Status`], for example the error document of the server
+    pub fn json<T: serde::de::DeserializeOwned>(&self) -> Option<serde_json::Result<T>> {
+        match self {
+            Error::Status(status) => Some(serde_json::from_slice(&status.body)),
+            _ => None,
+        }
+    }
+
+    /// The request timed out
+    pub fn is_timeout(&self) -> bool {
+        match self {
+            Error::Transport(error) => {
+                error
+                    .downcast_ref::<reqwest::Error>()
+                    .is_some_and(reqwest::Error::is_timeout)
+                    || error.is::<tokio::time::error::Elapsed>()
+            }
+            _ => false,
+        }
+    }
+
+    /// Connecting to the server failed
+    pub fn is_connect(&self) -> bool {
+        match self {
+            Error::Transport(error) => error
+                .downcast_ref::<reqwest::Error>()
+                .is_some_and(reqwest::Error::is_connect),
+            _ => false,
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Transport(error) => write!(f, "transport error: {}", error),
+            Error::Status(status) => write!(f, "http status error: {}", status.code),
+            Error::Decode { source, .. } => write!(f, "decode error: {}", source),
+            Error::Encode(error) => write!(f, "encode error: {}", error),
+            Error::Hook(error) => write!(f, "hook error: {}", error),
+            Error::InvalidUrl { url, source } => write!(f, "invalid url {}: {}", url, source),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Transport(error)
+            | Error::Decode { source: error, .. }
+            | Error::Encode(error)
+            | Error::Hook(error) => Some(&**error),
+            Error::InvalidUrl { source, .. } => Some(source),
+            Error::Status(_) => None,
+        }
+    }
+}
+
+/// Errors building the request are [`Error::Encode`], the others are [`Error::Transport`].
+impl From<reqwest::Error> for Error {
+    fn from(error: reqwest::Error) -> Self {
+        if error.is_builder() {
+            Error::Encode(error.into())
+        } else {
+            Error::Transport(error.into())
+        }
+    }
+}
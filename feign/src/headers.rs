@@ -0,0 +1,110 @@
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use serde::Serialize;
+
+use crate::query::scalar;
+use crate::{BoxError, Error};
+
+/// Headers of a request, the generated methods fill it in this order, a header set later replaces the one set before:
+///
+/// 1. `headers(...)` of `#[client]`
+/// 2. `headers(...)` of the method attribute
+/// 3. `#[header("Name")]` arguments
+/// 4. the `#[headers]` argument
+/// 5. the headers of the `#[args]` argument
+///
+/// `before_send` runs after them and can change any of them.
+///
+/// ```ignore
+/// #[client(host = "http://127.0.0.1:3000", path = "/user", headers("User-Agent" = "svc/1.0"))]
+/// pub trait UserClient {
+///     #[get(path = "/find_by_id/<id>", headers("Accept" = "application/json"))]
+///     async fn find_by_id(
+///         &self,
+///         #[path] id: i64,
+///         #[header("X-Request-Id")] request_id: Option<&str>,
+///     ) -> ClientResult<Option<User>>;
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Headers {
+    map: HeaderMap,
+}
+
+impl Headers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set a header
+    pub fn insert<K, V>(&mut self, name: K, value: V) -> Result<(), Error>
+    where
+        HeaderName: TryFrom<K>,
+        <HeaderName as TryFrom<K>>::Error: Into<BoxError>,
+        HeaderValue: TryFrom<V>,
+        <HeaderValue as TryFrom<V>>::Error: Into<BoxError>,
+    {
+        let (name, value) = pair(name, value)?;
+        self.map.insert(name, value);
+        Ok(())
+    }
+
+    /// Set a header from a scalar or an option, `None` is left out
+    pub fn insert_value<T: Serialize + ?Sized>(
+        &mut self,
+        name: &str,
+        value: &T,
+    ) -> Result<(), Error> {
+        let value = serde_json::to_value(value).map_err(Error::encode)?;
+        match scalar(value) {
+            Some(Some(value)) => self.insert(name, value),
+            Some(None) => Ok(()),
+            None => Err(Error::encode(format!(
+                "header {} must be a scalar or an option",
+                name
+            ))),
+        }
+    }
+
+    /// Set the headers of `headers`, a name repeated in it keeps all its values
+    pub fn extend<I, K, V>(&mut self, headers: I) -> Result<(), Error>
+    where
+        I: IntoIterator<Item = (K, V)>,
+        HeaderName: TryFrom<K>,
+        <HeaderName as TryFrom<K>>::Error: Into<BoxError>,
+        HeaderValue: TryFrom<V>,
+        <HeaderValue as TryFrom<V>>::Error: Into<BoxError>,
+    {
+        let mut map = HeaderMap::new();
+        for (name, value) in headers {
+            let (name, value) = pair(name, value)?;
+            map.append(name, value);
+        }
+        for name in map.keys() {
+            self.map.remove(name);
+        }
+        for (name, value) in map.iter() {
+            self.map.append(name, value.clone());
+        }
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn header_map(&self) -> &HeaderMap {
+        &self.map
+    }
+}
+
+fn pair<K, V>(name: K, value: V) -> Result<(HeaderName, HeaderValue), Error>
+where
+    HeaderName: TryFrom<K>,
+    <HeaderName as TryFrom<K>>::Error: Into<BoxError>,
+    HeaderValue: TryFrom<V>,
+    <HeaderValue as TryFrom<V>>::Error: Into<BoxError>,
+{
+    let name = HeaderName::try_from(name).map_err(Error::encode)?;
+    let value = HeaderValue::try_from(value).map_err(Error::encode)?;
+    Ok((name, value))
+}
@@ -1,16 +1,81 @@
 use std::any::Any;
 use std::fmt::{Debug, Display, Formatter};
 
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+
 pub use anyhow::Result as ClientResult;
+pub use body::IntoBody;
+pub use codec::Codec;
+pub use decoder::{Decoder, ResponseView, Text};
+pub use error::{BoxError, Error, StatusError};
 pub use feign_macros::*;
+pub use headers::Headers;
+pub use middleware::{Middleware, Middlewares, Next};
+pub use query::Query;
 pub use reqwest::RequestBuilder;
+pub use response::{RawResponse, Response};
+pub use retry::RetryPolicy;
+#[cfg(feature = "stream")]
+pub use sse::{Event, EventStream, Reconnect};
+#[cfg(feature = "stream")]
+pub use stream::{ByteStream, JsonLines};
+pub use transport::Transport;
+
+pub mod balance;
+mod body;
+pub mod codec;
+mod decoder;
+/// Clients generated from traits without async methods, enable the `blocking` feature.
+#[cfg(feature = "blocking")]
+pub mod blocking {
+    pub use middleware::{Middleware, Middlewares, Next};
+    pub use reqwest::blocking::{Client, ClientBuilder, Request, RequestBuilder, Response};
+    pub use transport::Transport;
 
+    mod middleware;
+    mod transport;
+
+    /// Blocking [`crate::build_response`], for the `after_receive` hook of blocking clients
+    pub fn build_response(
+        url: &reqwest::Url,
+        status: reqwest::StatusCode,
+        headers: reqwest::header::HeaderMap,
+        body: impl Into<bytes::Bytes>,
+    ) -> Response {
+        Response::from(crate::http_response(url, status, headers, body.into()))
+    }
+
+    /// Multipart bodies of the blocking clients, see [`crate::multipart`]
+    #[cfg(feature = "multipart")]
+    pub mod multipart;
+}
+mod error;
+mod headers;
+mod middleware;
+/// Multipart bodies, `#[multipart]` and `#[part]` arguments, enable the `multipart` feature.
+#[cfg(feature = "multipart")]
+pub mod multipart;
+mod query;
 pub mod re_exports;
+mod response;
+mod retry;
+#[cfg(feature = "stream")]
+mod sse;
+#[cfg(feature = "stream")]
+mod stream;
+/// Test clients without a socket, enable the `testing` feature.
+#[cfg(feature = "testing")]
+pub mod testing;
 #[cfg(test)]
 mod tests;
+/// Send the requests through tower services and layers, enable the `tower` feature.
+#[cfg(feature = "tower")]
+pub mod tower;
+/// Transports sending the requests instead of the reqwest client
+pub mod transport;
 
 /// Http methods enumed
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum HttpMethod {
     Get,
     Post,
@@ -25,10 +90,29 @@ pub enum RequestBody<T> {
     None,
     Json(T),
     Form(T),
+    /// A `#[multipart]` or `#[part]` body, the parts are streamed and not available here
+    Multipart,
+    /// A `#[body]` sent as it is
+    Raw(bytes::Bytes),
+    /// A `#[body]` stream, it is not available here
+    Stream,
 }
 
+/// Base url of the requests, `host` is called once for every request
+/// so implementations can balance requests between servers.
 pub trait Host: Display + Debug + Sync + Send + 'static {
     fn host(&self) -> &str;
+
+    /// Select the host of a request, defaults to [`Host::host`].
+    fn select(&self, request: &RequestContext) -> &str {
+        let _ = request;
+        self.host()
+    }
+
+    /// Called once for every selected host when the request is finished.
+    fn feedback(&self, host: &str, outcome: Outcome) {
+        let _ = (host, outcome);
+    }
 }
 
 impl Host for String {
@@ -37,8 +121,91 @@ impl Host for String {
     }
 }
 
+/// What the host selection knows about a request
+#[derive(Debug, Clone, Copy)]
+pub struct RequestContext<'a> {
+    pub method: HttpMethod,
+    /// Request path after the client path, with path variables replaced
+    pub path: &'a str,
+}
+
+/// What the `after_receive` hook knows about the request of a response
+///
+/// ```ignore
+/// async fn after_receive(
+///     response: reqwest::Response,
+///     request: &feign::RequestMeta<'_>,
+///     state: &Metrics,
+/// ) -> ClientResult<reqwest::Response> {
+///     state.observe(request.name, response.status(), request.elapsed);
+///     Ok(response)
+/// }
+///
+/// #[client(host = "http://127.0.0.1:3000", path = "/user", after_receive = "after_receive")]
+/// pub trait UserClient {}
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct RequestMeta<'a> {
+    /// Name of the trait method
+    pub name: &'static str,
+    pub method: HttpMethod,
+    /// Url the request was sent to, the response url differs after redirects
+    pub url: &'a reqwest::Url,
+    /// Request path after the client path, with path variables replaced
+    pub path: &'a str,
+    /// Attempt of the request, starting at 1
+    pub attempt: u32,
+    /// Time from sending the request to receiving the response headers
+    pub elapsed: std::time::Duration,
+}
+
+/// Result of a request sent to a selected host
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// Got a response, and it is not a server error
+    Success,
+    /// Connection failed, timed out, or the server responded 5xx
+    Failure,
+    /// The request was dropped before it finished
+    Cancelled,
+}
+
+/// A host selected for one request, reports [`Outcome::Cancelled`] if dropped without [`Selected::finish`].
+pub struct Selected<'a> {
+    host: &'a dyn Host,
+    selected: &'a str,
+    finished: bool,
+}
+
+impl<'a> Selected<'a> {
+    pub fn new(host: &'a dyn Host, request: &RequestContext) -> Self {
+        Self {
+            host,
+            selected: host.select(request),
+            finished: false,
+        }
+    }
+
+    pub fn host(&self) -> &'a str {
+        self.selected
+    }
+
+    pub fn finish(mut self, outcome: Outcome) {
+        self.finished = true;
+        self.host.feedback(self.selected, outcome);
+    }
+}
+
+impl Drop for Selected<'_> {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.host.feedback(self.selected, Outcome::Cancelled);
+        }
+    }
+}
+
 pub struct HostRound {
-    index: std::sync::Mutex<usize>,
+    index: std::sync::atomic::AtomicUsize,
     hosts: Vec<String>,
 }
 
@@ -48,7 +215,7 @@ impl HostRound {
             return Err(anyhow::anyhow!("HostRound hosts is empty"));
         }
         Ok(HostRound {
-            index: std::sync::Mutex::new(0),
+            index: std::sync::atomic::AtomicUsize::new(0),
             hosts,
         })
     }
@@ -70,26 +237,36 @@ impl Debug for HostRound {
 
 impl Host for HostRound {
     fn host(&self) -> &str {
-        let mut index = self.index.lock().unwrap();
-        let host = self.hosts.get(*index).unwrap();
-        *index = (*index + 1) % self.hosts.len();
-        host.as_str()
+        let index = self
+            .index
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        self.hosts[index % self.hosts.len()].as_str()
     }
 }
 
+/// State of a client, shared with the event streams it returns
 #[derive(Debug)]
 pub struct State<S = ()> {
-    value: Box<dyn Any + Send + Sync + 'static>,
+    value: std::sync::Arc<dyn Any + Send + Sync + 'static>,
     marker: std::marker::PhantomData<S>,
 }
 
+impl<S> Clone for State<S> {
+    fn clone(&self) -> Self {
+        Self {
+            value: self.value.clone(),
+            marker: std::marker::PhantomData,
+        }
+    }
+}
+
 impl<S> State<S>
 where
     S: Any + Send + Sync + 'static,
 {
     pub fn new(value: S) -> Self {
         Self {
-            value: Box::new(value),
+            value: std::sync::Arc::new(value),
             marker: std::marker::PhantomData,
         }
     }
@@ -107,10 +284,75 @@ where
     }
 }
 
-/// A deserialization function that converts bytes to a string.
-/// Example:
+/// Characters percent-encoded in a path variable, all but the unreserved and sub-delims characters, `:` and `@`
+const PATH_SEGMENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~')
+    .remove(b'!')
+    .remove(b'$')
+    .remove(b'&')
+    .remove(b'\'')
+    .remove(b'(')
+    .remove(b')')
+    .remove(b'*')
+    .remove(b'+')
+    .remove(b',')
+    .remove(b';')
+    .remove(b'=')
+    .remove(b':')
+    .remove(b'@');
+
+/// Percent-encode a path variable, so `/`, `?`, `#` and spaces stay inside one path segment.
+///
+/// Used by the generated methods for `#[path]`, `#[path(raw)]` inserts the value as is.
+pub fn encode_path<T: Display + ?Sized>(value: &T) -> String {
+    utf8_percent_encode(&value.to_string(), PATH_SEGMENT).to_string()
+}
+
+/// Build a response of `url`, for `after_receive` hooks replacing the body of a response
+///
+/// The `Content-Length` header is removed.
+pub fn build_response(
+    url: &reqwest::Url,
+    status: reqwest::StatusCode,
+    headers: reqwest::header::HeaderMap,
+    body: impl Into<bytes::Bytes>,
+) -> reqwest::Response {
+    reqwest::Response::from(http_response(url, status, headers, body.into()))
+}
+
+fn http_response(
+    url: &reqwest::Url,
+    status: reqwest::StatusCode,
+    mut headers: reqwest::header::HeaderMap,
+    body: bytes::Bytes,
+) -> http::Response<bytes::Bytes> {
+    headers.remove(reqwest::header::CONTENT_LENGTH);
+    let mut response = with_url(http::Response::new(body), url);
+    *response.status_mut() = status;
+    *response.headers_mut() = headers;
+    response
+}
+
+/// `response` with the url of its request, read by the conversion to a reqwest response,
+/// a url set by the transport, like the final url of redirects, is kept
+pub(crate) fn with_url<B>(response: http::Response<B>, url: &reqwest::Url) -> http::Response<B> {
+    let (mut parts, body) = response.into_parts();
+    let mut extensions = reqwest::ResponseBuilderExt::url(http::Response::builder(), url.clone())
+        .body(())
+        .map(|response| response.into_parts().0.extensions)
+        .unwrap_or_default();
+    extensions.extend(std::mem::take(&mut parts.extensions));
+    parts.extensions = extensions;
+    http::Response::from_parts(parts, body)
+}
+
+/// A deserialization function that converts bytes to a string, for `deserialize = "feign::text"`.
+/// Prefer the [`Text`] decoder:
 /// ```ignore
-///  #[post(path = "/new_user", deserialize = "feign::text")]
+///  #[post(path = "/new_user", deserialize = feign::Text)]
 ///  async fn new_user_text(&self, #[json] user: &User) -> ClientResult<String>;
 /// ```
 pub async fn text(body: &[u8]) -> ClientResult<String> {
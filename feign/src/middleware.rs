@@ -0,0 +1,120 @@
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use reqwest::{Client, Request, RequestBuilder, Response};
+
+use crate::transport::send_http;
+use crate::{Error, Transport};
+
+/// A layer around sending the requests of a client, added with `builder().layer(...)`
+///
+/// The first layer added runs first, it calls [`Next::run`] to pass the request to the next one,
+/// the last one sends it. Logging, auth, caching or metrics from different crates can be combined.
+///
+/// ```ignore
+/// struct Auth(String);
+///
+/// #[async_trait::async_trait]
+/// impl feign::Middleware for Auth {
+///     async fn handle(&self, mut request: reqwest::Request, next: feign::Next<'_>) -> Result<reqwest::Response, feign::Error> {
+///         request.headers_mut().insert("authorization", self.0.parse().map_err(feign::Error::hook)?);
+///         next.run(request).await
+///     }
+/// }
+///
+/// let client = UserClient::builder().layer(Auth("Bearer token".to_string())).build();
+/// ```
+///
+/// Layers run for every attempt of the retry policy, after `before_send` and before `after_receive`.
+#[async_trait]
+pub trait Middleware: Send + Sync + 'static {
+    async fn handle(&self, request: Request, next: Next<'_>) -> Result<Response, Error>;
+}
+
+/// The layers after the current one, copy it to send a request more than once
+#[derive(Clone, Copy)]
+pub struct Next<'a> {
+    client: &'a Client,
+    transport: Option<&'a dyn Transport>,
+    middlewares: &'a [Arc<dyn Middleware>],
+}
+
+impl<'a> Next<'a> {
+    pub fn new(
+        client: &'a Client,
+        transport: Option<&'a dyn Transport>,
+        middlewares: &'a [Arc<dyn Middleware>],
+    ) -> Self {
+        Self {
+            client,
+            transport,
+            middlewares,
+        }
+    }
+
+    /// Client sending the request after the last layer
+    pub fn client(&self) -> &'a Client {
+        self.client
+    }
+
+    pub async fn run(self, request: Request) -> Result<Response, Error> {
+        match self.middlewares.split_first() {
+            Some((middleware, middlewares)) => {
+                middleware
+                    .handle(request, Next::new(self.client, self.transport, middlewares))
+                    .await
+            }
+            None => match self.transport {
+                Some(transport) => send_http(request, |request| transport.send(request)).await,
+                None => self.client.execute(request).await.map_err(Error::from),
+            },
+        }
+    }
+}
+
+/// Layers of a client, in the order they were added, and the transport after them
+#[derive(Clone, Default)]
+pub struct Middlewares {
+    middlewares: Vec<Arc<dyn Middleware>>,
+    transport: Option<Arc<dyn Transport>>,
+}
+
+impl Middlewares {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, middleware: impl Middleware) {
+        self.middlewares.push(Arc::new(middleware));
+    }
+
+    /// Send with `transport` instead of the reqwest client
+    pub fn set_transport(&mut self, transport: impl Transport) {
+        self.transport = Some(Arc::new(transport));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.middlewares.is_empty()
+    }
+
+    /// Send the request through the layers, used by the generated methods
+    pub async fn send(&self, request: RequestBuilder) -> Result<Response, Error> {
+        if self.middlewares.is_empty() && self.transport.is_none() {
+            return request.send().await.map_err(Error::from);
+        }
+        let (client, request) = request.build_split();
+        Next::new(&client, self.transport.as_deref(), &self.middlewares)
+            .run(request?)
+            .await
+    }
+}
+
+impl Debug for Middlewares {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Middlewares")
+            .field("len", &self.middlewares.len())
+            .field("transport", &self.transport.is_some())
+            .finish()
+    }
+}
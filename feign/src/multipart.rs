@@ -0,0 +1,79 @@
+use std::future::Future;
+use std::path::{Path, PathBuf};
+
+use bytes::Bytes;
+pub use reqwest::multipart::{Form, Part};
+
+use crate::Error;
+
+/// Value of a `#[part]` argument
+///
+/// Bytes and strings are sent as they are, a [`tokio::fs::File`] is streamed,
+/// and a path opens the file, with the file name and the content type guessed from its extension.
+///
+/// ```ignore
+/// #[post(path = "/avatar")]
+/// async fn upload(
+///     &self,
+///     #[part] user_id: String,
+///     #[part(name = "file", content_type = "image/png")] avatar: PathBuf,
+///     #[part(filename = "notes.txt")] notes: Vec<u8>,
+/// ) -> ClientResult<String>;
+/// ```
+pub trait IntoPart {
+    fn into_part(self) -> impl Future<Output = Result<Part, Error>> + Send;
+}
+
+impl IntoPart for Part {
+    async fn into_part(self) -> Result<Part, Error> {
+        Ok(self)
+    }
+}
+
+impl IntoPart for Vec<u8> {
+    async fn into_part(self) -> Result<Part, Error> {
+        Ok(Part::bytes(self))
+    }
+}
+
+impl IntoPart for &[u8] {
+    async fn into_part(self) -> Result<Part, Error> {
+        Ok(Part::bytes(self.to_vec()))
+    }
+}
+
+impl IntoPart for Bytes {
+    async fn into_part(self) -> Result<Part, Error> {
+        Ok(Part::stream(self))
+    }
+}
+
+impl IntoPart for String {
+    async fn into_part(self) -> Result<Part, Error> {
+        Ok(Part::text(self))
+    }
+}
+
+impl IntoPart for &str {
+    async fn into_part(self) -> Result<Part, Error> {
+        Ok(Part::text(self.to_string()))
+    }
+}
+
+impl IntoPart for tokio::fs::File {
+    async fn into_part(self) -> Result<Part, Error> {
+        Ok(Part::stream(self))
+    }
+}
+
+impl IntoPart for &Path {
+    async fn into_part(self) -> Result<Part, Error> {
+        Part::file(self).await.map_err(Error::encode)
+    }
+}
+
+impl IntoPart for PathBuf {
+    async fn into_part(self) -> Result<Part, Error> {
+        Part::file(self).await.map_err(Error::encode)
+    }
+}
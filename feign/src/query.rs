@@ -0,0 +1,124 @@
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::Error;
+
+/// Query string of a request, the generated methods fill it from the `#[query]` arguments
+///
+/// ```ignore
+/// #[get(path = "/users")]
+/// async fn users(
+///     &self,
+///     #[query] page: u32,
+///     #[query(name = "page-size")] page_size: Option<u32>,
+///     #[query(join = ",")] fields: &[&str],
+///     #[query(flatten)] filter: &Filter,
+/// ) -> ClientResult<Vec<User>>;
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Query {
+    pairs: Vec<(String, String)>,
+}
+
+impl Query {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `name=value`.
+    ///
+    /// `None` adds nothing, a sequence adds `name=item` for every item,
+    /// or a single pair with the items joined by `join`.
+    pub fn push<T: Serialize + ?Sized>(
+        &mut self,
+        name: &str,
+        value: &T,
+        join: Option<&str>,
+    ) -> Result<(), Error> {
+        let value = serde_json::to_value(value).map_err(Error::encode)?;
+        self.push_value(name, value, join)
+    }
+
+    /// Add every field of a struct or map as [`Query::push`] does,
+    /// a sequence of `(name, value)` pairs is accepted as well.
+    pub fn flatten<T: Serialize + ?Sized>(
+        &mut self,
+        value: &T,
+        join: Option<&str>,
+    ) -> Result<(), Error> {
+        match serde_json::to_value(value).map_err(Error::encode)? {
+            Value::Null => Ok(()),
+            Value::Object(fields) => fields
+                .into_iter()
+                .try_for_each(|(name, value)| self.push_value(&name, value, join)),
+            Value::Array(pairs) => pairs.into_iter().try_for_each(|pair| match pair {
+                Value::Array(pair) if pair.len() == 2 => {
+                    let mut pair = pair.into_iter();
+                    let name = pair.next().unwrap();
+                    let value = pair.next().unwrap();
+                    match scalar(name) {
+                        Some(Some(name)) => self.push_value(&name, value, join),
+                        _ => Err(Error::encode(
+                            "query pair name must be a string or a number",
+                        )),
+                    }
+                }
+                _ => Err(Error::encode(
+                    "flattened query sequence must hold (name, value) pairs",
+                )),
+            }),
+            _ => Err(Error::encode(
+                "flattened query must be a struct, a map or a sequence of pairs",
+            )),
+        }
+    }
+
+    fn push_value(&mut self, name: &str, value: Value, join: Option<&str>) -> Result<(), Error> {
+        let invalid = || {
+            Error::encode(format!(
+                "query {} must be a scalar, an option or a sequence of scalars",
+                name
+            ))
+        };
+        match value {
+            Value::Array(items) => {
+                let items = items
+                    .into_iter()
+                    .filter_map(|item| scalar(item).ok_or_else(invalid).transpose())
+                    .collect::<Result<Vec<_>, _>>()?;
+                match join {
+                    Some(_) if items.is_empty() => {}
+                    Some(join) => self.pairs.push((name.to_string(), items.join(join))),
+                    None => self
+                        .pairs
+                        .extend(items.into_iter().map(|item| (name.to_string(), item))),
+                }
+            }
+            value => {
+                if let Some(value) = scalar(value).ok_or_else(invalid)? {
+                    self.pairs.push((name.to_string(), value));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    pub fn pairs(&self) -> &[(String, String)] {
+        &self.pairs
+    }
+}
+
+/// Text of a scalar, `Some(None)` for null, `None` when it is not a scalar
+pub(crate) fn scalar(value: Value) -> Option<Option<String>> {
+    match value {
+        Value::Null => Some(None),
+        Value::Bool(value) => Some(Some(value.to_string())),
+        Value::Number(value) => Some(Some(value.to_string())),
+        Value::String(value) => Some(Some(value)),
+        Value::Array(_) | Value::Object(_) => None,
+    }
+}
@@ -0,0 +1 @@
+pub use async_trait::*;
@@ -0,0 +1 @@
+pub use http::*;
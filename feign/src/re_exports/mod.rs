@@ -1,5 +1,11 @@
 pub mod anyhow;
+pub mod async_trait;
+pub mod bytes;
+pub mod http;
 pub mod reqwest;
 pub mod serde;
 pub mod serde_derive;
 pub mod serde_json;
+pub mod tokio;
+#[cfg(feature = "tower")]
+pub mod tower;
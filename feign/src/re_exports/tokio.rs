@@ -0,0 +1 @@
+pub use tokio::*;
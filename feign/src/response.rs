@@ -0,0 +1,42 @@
+use bytes::Bytes;
+use reqwest::header::HeaderMap;
+use reqwest::{StatusCode, Url};
+
+/// Return `ClientResult<Response<T>>` to get the status, headers and final url
+/// along with the deserialized body.
+///
+/// ```ignore
+/// #[get(path = "/users")]
+/// async fn users(&self, #[query] page: u32) -> ClientResult<Response<Vec<User>>>;
+/// ```
+#[derive(Debug, Clone)]
+pub struct Response<T> {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    /// Url of the response, after the redirects
+    pub url: Url,
+    pub body: T,
+}
+
+/// Response with the body not deserialized
+pub type RawResponse = Response<Bytes>;
+
+impl<T> Response<T> {
+    /// Value of a header, if it is present and visible ASCII
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name)?.to_str().ok()
+    }
+
+    pub fn into_body(self) -> T {
+        self.body
+    }
+
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Response<U> {
+        Response {
+            status: self.status,
+            headers: self.headers,
+            url: self.url,
+            body: f(self.body),
+        }
+    }
+}
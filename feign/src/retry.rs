@@ -0,0 +1,153 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+
+use crate::{Error, HttpMethod};
+
+/// When and how long to wait before sending a request again
+///
+/// Set it with `retry = "policy_fn"` on `#[client]` or on a method, `policy_fn` returns the policy
+///
+/// ```ignore
+/// fn retry() -> feign::RetryPolicy {
+///     feign::RetryPolicy::new().with_max_attempts(5)
+/// }
+///
+/// #[client(host = "http://127.0.0.1:3000", path = "/user", retry = "retry")]
+/// pub trait UserClient {}
+/// ```
+///
+/// Every attempt selects the host again, so with a load balancer the retries move to another host.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Attempts including the first one
+    pub max_attempts: u32,
+    /// Wait before the second attempt
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// The wait is multiplied by this after every attempt
+    pub multiplier: f64,
+    /// Random part of the wait, between 0 and 1, `0.5` waits between 50% and 100% of the backoff
+    pub jitter: f64,
+    /// Retry the responses with these statuses
+    pub retry_on: Vec<StatusCode>,
+    /// Retry POST and PATCH as well, by default they are only retried when the connection failed
+    pub retry_non_idempotent: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(10),
+            multiplier: 2.0,
+            jitter: 0.5,
+            retry_on: vec![
+                StatusCode::TOO_MANY_REQUESTS,
+                StatusCode::BAD_GATEWAY,
+                StatusCode::SERVICE_UNAVAILABLE,
+                StatusCode::GATEWAY_TIMEOUT,
+            ],
+            retry_non_idempotent: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Never retry, use it on a method to disable the retry policy of the client.
+    pub fn none() -> Self {
+        Self::default().with_max_attempts(1)
+    }
+
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    pub fn with_backoff(mut self, initial_backoff: Duration, max_backoff: Duration) -> Self {
+        self.initial_backoff = initial_backoff;
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    pub fn with_multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+
+    pub fn with_jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_retry_on(mut self, retry_on: Vec<StatusCode>) -> Self {
+        self.retry_on = retry_on;
+        self
+    }
+
+    pub fn with_retry_non_idempotent(mut self, retry_non_idempotent: bool) -> Self {
+        self.retry_non_idempotent = retry_non_idempotent;
+        self
+    }
+
+    /// GET, HEAD, PUT and DELETE can be sent twice without changing the result
+    pub fn is_idempotent(method: HttpMethod) -> bool {
+        matches!(
+            method,
+            HttpMethod::Get | HttpMethod::Head | HttpMethod::Put | HttpMethod::Delete
+        )
+    }
+
+    /// Wait before the `attempt + 1`th attempt, without jitter
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let backoff = self.initial_backoff.as_secs_f64()
+            * self.multiplier.powi(attempt.saturating_sub(1) as i32);
+        Duration::from_secs_f64(backoff.min(self.max_backoff.as_secs_f64()))
+    }
+
+    fn jittered(&self, backoff: Duration) -> Duration {
+        let random =
+            (RandomState::new().build_hasher().finish() >> 11) as f64 / (1u64 << 53) as f64;
+        backoff.mul_f64(1.0 - self.jitter * random)
+    }
+
+    /// How long to wait before sending again, `None` when the `attempt`th attempt must not be retried.
+    ///
+    /// `response` is the status and headers of the response, or the error sending the request.
+    pub fn retry_after(
+        &self,
+        method: HttpMethod,
+        attempt: u32,
+        response: Result<(StatusCode, &HeaderMap), &Error>,
+    ) -> Option<Duration> {
+        if attempt >= self.max_attempts {
+            return None;
+        }
+        let retry = self.retry_non_idempotent || Self::is_idempotent(method);
+        match response {
+            Ok((status, headers)) if retry && self.retry_on.contains(&status) => {
+                let backoff = self.jittered(self.backoff(attempt));
+                // the server knows better, but do not wait longer than max_backoff
+                let retry_after = headers
+                    .get(reqwest::header::RETRY_AFTER)
+                    .and_then(|value| value.to_str().ok()?.trim().parse().ok())
+                    .map(Duration::from_secs)
+                    .unwrap_or_default();
+                Some(backoff.max(retry_after).min(self.max_backoff))
+            }
+            Ok(_) => None,
+            // the request was not sent
+            Err(error) if error.is_connect() => Some(self.jittered(self.backoff(attempt))),
+            Err(Error::Transport(_)) if retry => Some(self.jittered(self.backoff(attempt))),
+            Err(_) => None,
+        }
+    }
+}
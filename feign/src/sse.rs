@@ -0,0 +1,370 @@
+use std::fmt::{Debug, Formatter};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use bytes::Bytes;
+use futures_core::Stream;
+use futures_util::StreamExt;
+use reqwest::header::HeaderValue;
+use reqwest::{RequestBuilder, Response, StatusCode, Url};
+use serde::de::DeserializeOwned;
+
+use crate::{
+    ByteStream, Error, Host, HttpMethod, Middlewares, Outcome, RequestContext, RequestMeta,
+    Selected, StatusError,
+};
+
+/// Wait before reconnecting, until the server sends a `retry` field
+const DEFAULT_RETRY: Duration = Duration::from_secs(3);
+
+/// One event of a `text/event-stream` response
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event<T> {
+    /// `event` field, `"message"` when the server sent none
+    pub event: String,
+    /// Last event id, sent back as `Last-Event-ID` when reconnecting
+    pub id: Option<String>,
+    /// The `data` lines deserialized from JSON, or as a string when they are not JSON
+    pub data: T,
+    /// Reconnection delay sent along with this event
+    pub retry: Option<Duration>,
+}
+
+/// Server-Sent Events, return it from a method to receive the events as they are pushed
+///
+/// When the connection ends, the request is sent again with the `Last-Event-ID` header,
+/// after the delay asked by the server or 3 seconds. It is sent like the first one,
+/// to a host selected again, through the middlewares, the transport and `after_receive`.
+/// The stream ends when the server answers `204 No Content`, or with an error status.
+///
+/// ```ignore
+/// #[get(path = "/events")]
+/// async fn events(&self) -> ClientResult<feign::EventStream<Update>>;
+/// ```
+pub struct EventStream<T> {
+    inner: Pin<Box<dyn Stream<Item = Result<Event<T>, Error>> + Send>>,
+}
+
+impl<T: DeserializeOwned + Send + 'static> EventStream<T> {
+    /// Events of `response`, `reconnect` is the request sent again when the connection ends
+    pub fn new(response: Response, reconnect: Option<Reconnect>) -> Self {
+        let state = Connection {
+            body: Some(ByteStream::from_response(response)),
+            parser: EventParser::default(),
+            reconnect,
+        };
+        Self {
+            inner: Box::pin(futures_util::stream::unfold(state, Connection::next)),
+        }
+    }
+}
+
+impl<T> Stream for EventStream<T> {
+    type Item = Result<Event<T>, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
+
+impl<T> Debug for EventStream<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventStream").finish_non_exhaustive()
+    }
+}
+
+/// The `after_receive` hook of a client, called with the responses of the reconnections
+type AfterReceive = Arc<
+    dyn for<'a> Fn(
+            Response,
+            &'a RequestMeta<'a>,
+        ) -> Pin<Box<dyn Future<Output = Result<Response, Error>> + Send + 'a>>
+        + Send
+        + Sync,
+>;
+
+/// Sends the request of an [`EventStream`] again, built by the generated methods
+pub struct Reconnect {
+    host: Arc<dyn Host>,
+    method: HttpMethod,
+    /// Path of the client
+    base: String,
+    /// Path of the request after the client path
+    path: String,
+    request: RequestBuilder,
+    middlewares: Middlewares,
+    after_receive: Option<(&'static str, AfterReceive)>,
+}
+
+impl Reconnect {
+    /// Send `request` again, to a host of `host` selected for every reconnection
+    pub fn new(
+        host: Arc<dyn Host>,
+        method: HttpMethod,
+        base: String,
+        path: String,
+        request: RequestBuilder,
+        middlewares: Middlewares,
+    ) -> Self {
+        Self {
+            host,
+            method,
+            base,
+            path,
+            request,
+            middlewares,
+            after_receive: None,
+        }
+    }
+
+    /// Pass the responses to the `after_receive` hook of the client, `name` is the method name
+    pub fn with_after_receive<F>(mut self, name: &'static str, after_receive: F) -> Self
+    where
+        F: for<'a> Fn(
+                Response,
+                &'a RequestMeta<'a>,
+            )
+                -> Pin<Box<dyn Future<Output = Result<Response, Error>> + Send + 'a>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.after_receive = Some((name, Arc::new(after_receive)));
+        self
+    }
+
+    async fn send(&self, last_event_id: Option<&str>) -> Result<Response, Error> {
+        let request = self
+            .request
+            .try_clone()
+            .ok_or_else(|| Error::Transport("the request can not be sent again".into()))?;
+        let (client, request) = request.build_split();
+        let mut request = request?;
+        let selected = Selected::new(
+            &*self.host,
+            &RequestContext {
+                method: self.method,
+                path: &self.path,
+            },
+        );
+        let url = format!("{}{}{}", selected.host(), self.base, self.path);
+        let mut url = Url::parse(&url).map_err(|source| Error::InvalidUrl { url, source })?;
+        url.set_query(request.url().query());
+        *request.url_mut() = url.clone();
+        if let Some(id) = last_event_id.and_then(|id| HeaderValue::from_str(id).ok()) {
+            request.headers_mut().insert("Last-Event-ID", id);
+        }
+        let started = Instant::now();
+        let response = self
+            .middlewares
+            .send(RequestBuilder::from_parts(client, request))
+            .await;
+        selected.finish(match &response {
+            Ok(response) if !response.status().is_server_error() => Outcome::Success,
+            _ => Outcome::Failure,
+        });
+        match (response, &self.after_receive) {
+            (Ok(response), Some((name, after_receive))) => {
+                let request = RequestMeta {
+                    name,
+                    method: self.method,
+                    url: &url,
+                    path: &self.path,
+                    attempt: 1,
+                    elapsed: started.elapsed(),
+                };
+                after_receive(response, &request).await
+            }
+            (response, _) => response,
+        }
+    }
+}
+
+impl Debug for Reconnect {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Reconnect")
+            .field("host", &self.host)
+            .field("method", &self.method)
+            .field("path", &self.path)
+            .field("middlewares", &self.middlewares)
+            .finish_non_exhaustive()
+    }
+}
+
+struct Connection {
+    body: Option<ByteStream>,
+    parser: EventParser,
+    reconnect: Option<Reconnect>,
+}
+
+impl Connection {
+    async fn next<T: DeserializeOwned>(mut self) -> Option<(Result<Event<T>, Error>, Self)> {
+        loop {
+            if let Some(frame) = self.parser.next_frame() {
+                return Some((frame.decode(), self));
+            }
+            match &mut self.body {
+                Some(body) => match body.next().await {
+                    Some(Ok(chunk)) => self.parser.feed(&chunk),
+                    Some(Err(err)) => {
+                        self.body = None;
+                        self.parser.disconnected();
+                        return Some((Err(err), self));
+                    }
+                    None => {
+                        self.body = None;
+                        self.parser.disconnected();
+                    }
+                },
+                None => {
+                    let reconnect = self.reconnect.as_ref()?;
+                    tokio::time::sleep(self.parser.retry.unwrap_or(DEFAULT_RETRY)).await;
+                    match reconnect.send(self.parser.last_event_id.as_deref()).await {
+                        // the server has no more events
+                        Ok(response) if response.status() == StatusCode::NO_CONTENT => return None,
+                        Ok(response) if response.status().is_success() => {
+                            self.body = Some(ByteStream::from_response(response));
+                        }
+                        Ok(response) => {
+                            self.reconnect = None;
+                            let code = response.status();
+                            let headers = response.headers().clone();
+                            let body = match response.bytes().await {
+                                Ok(body) => body,
+                                Err(err) => return Some((Err(Error::from(err)), self)),
+                            };
+                            return Some((
+                                Err(Error::Status(Box::new(StatusError {
+                                    code,
+                                    headers,
+                                    body,
+                                }))),
+                                self,
+                            ));
+                        }
+                        // sent again on the next poll
+                        Err(err) => return Some((Err(err), self)),
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// An event before its data is deserialized
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct Frame {
+    pub(crate) event: String,
+    pub(crate) id: Option<String>,
+    pub(crate) data: String,
+    pub(crate) retry: Option<Duration>,
+}
+
+impl Frame {
+    fn decode<T: DeserializeOwned>(self) -> Result<Event<T>, Error> {
+        let data = serde_json::from_str(&self.data).or_else(|err| {
+            serde_json::from_value(serde_json::Value::String(self.data.clone()))
+                .map_err(|_| Error::decode(Bytes::from(self.data), err))
+        })?;
+        Ok(Event {
+            event: self.event,
+            id: self.id,
+            data,
+            retry: self.retry,
+        })
+    }
+}
+
+/// Parser of the `text/event-stream` format
+#[derive(Debug, Default)]
+pub(crate) struct EventParser {
+    buffer: Vec<u8>,
+    event: String,
+    data: Option<String>,
+    retry: Option<Duration>,
+    frame_retry: Option<Duration>,
+    last_event_id: Option<String>,
+}
+
+impl EventParser {
+    pub(crate) fn feed(&mut self, chunk: &[u8]) {
+        self.buffer.extend_from_slice(chunk);
+    }
+
+    /// The connection ended, the event not finished by an empty line is dropped
+    pub(crate) fn disconnected(&mut self) {
+        self.buffer.clear();
+        self.event.clear();
+        self.data = None;
+        self.frame_retry = None;
+    }
+
+    pub(crate) fn next_frame(&mut self) -> Option<Frame> {
+        while let Some(line) = self.next_line() {
+            if line.is_empty() {
+                let event = std::mem::take(&mut self.event);
+                if let Some(mut data) = self.data.take() {
+                    data.pop();
+                    return Some(Frame {
+                        event: if event.is_empty() {
+                            "message".to_string()
+                        } else {
+                            event
+                        },
+                        id: self.last_event_id.clone(),
+                        data,
+                        retry: self.frame_retry.take(),
+                    });
+                }
+                self.frame_retry = None;
+                continue;
+            }
+            let line = String::from_utf8_lossy(&line);
+            // comment
+            if line.starts_with(':') {
+                continue;
+            }
+            let (field, value) = match line.split_once(':') {
+                Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+                None => (line.as_ref(), ""),
+            };
+            match field {
+                "event" => self.event = value.to_string(),
+                "data" => {
+                    let data = self.data.get_or_insert_with(String::new);
+                    data.push_str(value);
+                    data.push('\n');
+                }
+                "id" if !value.contains('\0') => self.last_event_id = Some(value.to_string()),
+                "retry" => {
+                    if let Ok(millis) = value.parse() {
+                        self.retry = Some(Duration::from_millis(millis));
+                        self.frame_retry = self.retry;
+                    }
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    /// A line ended by `\n`, `\r\n` or `\r`, without its end
+    fn next_line(&mut self) -> Option<Vec<u8>> {
+        let end = self
+            .buffer
+            .iter()
+            .position(|b| *b == b'\n' || *b == b'\r')?;
+        let len = match self.buffer[end..] {
+            [b'\r', b'\n', ..] => 2,
+            // wait for the next chunk, it may start with \n
+            [b'\r'] => return None,
+            _ => 1,
+        };
+        let mut line: Vec<u8> = self.buffer.drain(..end + len).collect();
+        line.truncate(end);
+        Some(line)
+    }
+}
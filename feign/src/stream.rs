@@ -0,0 +1,149 @@
+use std::fmt::{Debug, Formatter};
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+use bytes::Bytes;
+use futures_core::Stream;
+use serde::de::DeserializeOwned;
+use tokio::io::AsyncRead;
+
+use crate::Error;
+
+/// Response body read chunk by chunk, return it to stream a download instead of buffering it
+///
+/// ```ignore
+/// #[get(path = "/export")]
+/// async fn export(&self) -> ClientResult<feign::ByteStream>;
+/// #[get(path = "/export")]
+/// async fn export_reader(&self) -> ClientResult<impl AsyncRead + Send + Unpin>;
+/// ```
+pub struct ByteStream {
+    inner: Pin<Box<dyn Stream<Item = Result<Bytes, Error>> + Send>>,
+}
+
+impl ByteStream {
+    pub fn new(stream: impl Stream<Item = Result<Bytes, Error>> + Send + 'static) -> Self {
+        Self {
+            inner: Box::pin(stream),
+        }
+    }
+
+    pub fn from_response(response: reqwest::Response) -> Self {
+        Self::new(ResponseStream(Box::pin(response.bytes_stream())))
+    }
+
+    /// Read the body with [`AsyncRead`], errors become `std::io::Error`
+    pub fn into_async_read(self) -> impl AsyncRead + Send + Unpin {
+        tokio_util::io::StreamReader::new(IoStream(self))
+    }
+}
+
+impl Stream for ByteStream {
+    type Item = Result<Bytes, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
+
+impl Debug for ByteStream {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ByteStream").finish_non_exhaustive()
+    }
+}
+
+struct ResponseStream<S>(Pin<Box<S>>);
+
+impl<S> Stream for ResponseStream<S>
+where
+    S: Stream<Item = reqwest::Result<Bytes>>,
+{
+    type Item = Result<Bytes, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Poll::Ready(match ready!(self.0.as_mut().poll_next(cx)) {
+            Some(Ok(chunk)) => Some(Ok(chunk)),
+            Some(Err(err)) => Some(Err(Error::from(err))),
+            None => None,
+        })
+    }
+}
+
+struct IoStream(ByteStream);
+
+impl Stream for IoStream {
+    type Item = std::io::Result<Bytes>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let item = ready!(Pin::new(&mut self.0).poll_next(cx));
+        Poll::Ready(item.map(|item| item.map_err(std::io::Error::other)))
+    }
+}
+
+/// Newline-delimited JSON response, every line is deserialized when it arrives
+///
+/// ```ignore
+/// #[get(path = "/events")]
+/// async fn events(&self) -> ClientResult<feign::JsonLines<Event>>;
+/// ```
+pub struct JsonLines<T> {
+    bytes: ByteStream,
+    buffer: Vec<u8>,
+    done: bool,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> JsonLines<T> {
+    pub fn new(bytes: ByteStream) -> Self {
+        Self {
+            bytes,
+            buffer: vec![],
+            done: false,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T: DeserializeOwned> Stream for JsonLines<T> {
+    type Item = Result<T, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            let line = match this.buffer.iter().position(|b| *b == b'\n') {
+                Some(end) => Some(this.buffer.drain(..=end).collect::<Vec<_>>()),
+                // the last line may not end with a newline
+                None if this.done && !this.buffer.is_empty() => {
+                    Some(std::mem::take(&mut this.buffer))
+                }
+                None if this.done => return Poll::Ready(None),
+                None => None,
+            };
+            if let Some(line) = line {
+                if line.trim_ascii().is_empty() {
+                    continue;
+                }
+                let line = Bytes::from(line);
+                return Poll::Ready(Some(
+                    serde_json::from_slice(&line).map_err(|err| Error::decode(line, err)),
+                ));
+            }
+            match ready!(Pin::new(&mut this.bytes).poll_next(cx)) {
+                Some(Ok(chunk)) => this.buffer.extend_from_slice(&chunk),
+                Some(Err(err)) => {
+                    this.done = true;
+                    this.buffer.clear();
+                    return Poll::Ready(Some(Err(err)));
+                }
+                None => this.done = true,
+            }
+        }
+    }
+}
+
+impl<T> Debug for JsonLines<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("JsonLines").finish_non_exhaustive()
+    }
+}
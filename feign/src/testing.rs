@@ -0,0 +1,365 @@
+use std::fmt::{Debug, Display, Formatter};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use http_body_util::BodyExt;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE};
+use reqwest::{Body, Method, StatusCode};
+use serde::Serialize;
+
+use crate::{BoxError, HttpMethod, Transport};
+
+pub use cassette::{Cassette, CassetteMode, Matching};
+
+mod cassette;
+
+/// Answers the requests of a client in memory, so it is tested without a socket
+///
+/// Register the expected requests and their responses, send the requests
+/// with [`MockServer::transport`], then check the calls with [`MockServer::verify`].
+///
+/// ```ignore
+/// let server = MockServer::new();
+/// let find = server.mock(
+///     Mock::new(HttpMethod::Get, "/user/find_by_id/1")
+///         .respond_with(MockResponse::new(200).with_json(&user))
+///         .expect(1),
+/// );
+/// let client = UserClient::builder().with_transport(server.transport()).build();
+///
+/// assert_eq!(client.find_by_id(1).await?, user);
+/// assert_eq!(find.hits(), 1);
+/// server.verify();
+/// ```
+///
+/// A request matches the first mock registered with its method, path, query pairs, headers and body,
+/// a request matching no mock fails with [`crate::Error::Transport`].
+#[derive(Debug, Clone, Default)]
+pub struct MockServer {
+    inner: Arc<Mutex<Inner>>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    mocks: Vec<(Mock, usize)>,
+    received: Vec<ReceivedRequest>,
+    unmatched: Vec<ReceivedRequest>,
+}
+
+impl MockServer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answer the requests matching `mock` with its response
+    pub fn mock(&self, mock: Mock) -> MockHandle {
+        let mut inner = self.inner.lock().unwrap();
+        inner.mocks.push((mock, 0));
+        MockHandle {
+            server: self.clone(),
+            index: inner.mocks.len() - 1,
+        }
+    }
+
+    /// Transport of the clients under test, for `builder().with_transport(...)`
+    pub fn transport(&self) -> MockTransport {
+        MockTransport {
+            server: self.clone(),
+        }
+    }
+
+    /// Every request received, in order
+    pub fn received(&self) -> Vec<ReceivedRequest> {
+        self.inner.lock().unwrap().received.clone()
+    }
+
+    /// Panics if a mock was not called as many times as expected, or a request matched no mock
+    pub fn verify(&self) {
+        let inner = self.inner.lock().unwrap();
+        let mut failures = vec![];
+        for (mock, hits) in &inner.mocks {
+            if let Some(expected) = mock.expected {
+                if expected != *hits {
+                    failures.push(format!(
+                        "{} expected {} calls, got {}",
+                        mock, expected, hits
+                    ));
+                }
+            }
+        }
+        for request in &inner.unmatched {
+            failures.push(format!("no mock matches {}", request));
+        }
+        if !failures.is_empty() {
+            panic!("mock server verify failed:\n{}", failures.join("\n"));
+        }
+    }
+
+    /// Response of the first matching mock
+    fn respond(&self, request: ReceivedRequest) -> Result<http::Response<Bytes>, BoxError> {
+        let mut inner = self.inner.lock().unwrap();
+        inner.received.push(request.clone());
+        let mock = inner
+            .mocks
+            .iter_mut()
+            .find(|(mock, _)| mock.matches(&request));
+        match mock {
+            Some((mock, hits)) => {
+                *hits += 1;
+                Ok(mock.response.to_http())
+            }
+            None => {
+                let error = format!("no mock matches {}", request);
+                inner.unmatched.push(request);
+                Err(error.into())
+            }
+        }
+    }
+}
+
+/// A registered mock, tells how many times it was called
+#[derive(Debug, Clone)]
+pub struct MockHandle {
+    server: MockServer,
+    index: usize,
+}
+
+impl MockHandle {
+    pub fn hits(&self) -> usize {
+        self.server.inner.lock().unwrap().mocks[self.index].1
+    }
+}
+
+/// Transport answering with the mocks of a [`MockServer`]
+#[derive(Debug, Clone)]
+pub struct MockTransport {
+    server: MockServer,
+}
+
+#[async_trait]
+impl Transport for MockTransport {
+    async fn send(&self, request: http::Request<Body>) -> Result<http::Response<Body>, BoxError> {
+        let (parts, body) = request.into_parts();
+        // streamed bodies are read to match them
+        let body = body.collect().await?.to_bytes();
+        let response = self.server.respond(ReceivedRequest::new(parts, body))?;
+        Ok(response.map(Body::from))
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl crate::blocking::Transport for MockTransport {
+    fn send(&self, request: http::Request<Bytes>) -> Result<http::Response<Bytes>, BoxError> {
+        let (parts, body) = request.into_parts();
+        self.server.respond(ReceivedRequest::new(parts, body))
+    }
+}
+
+/// A request received by a [`MockServer`]
+#[derive(Debug, Clone)]
+pub struct ReceivedRequest {
+    pub method: Method,
+    pub path: String,
+    /// Decoded query pairs
+    pub query: Vec<(String, String)>,
+    pub headers: HeaderMap,
+    pub body: Bytes,
+}
+
+impl ReceivedRequest {
+    fn new(parts: http::request::Parts, body: Bytes) -> Self {
+        let query = parts
+            .uri
+            .query()
+            .map(|query| {
+                url::form_urlencoded::parse(query.as_bytes())
+                    .into_owned()
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self {
+            method: parts.method,
+            path: parts.uri.path().to_string(),
+            query,
+            headers: parts.headers,
+            body,
+        }
+    }
+}
+
+impl Display for ReceivedRequest {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.method, self.path)?;
+        for (index, (name, value)) in self.query.iter().enumerate() {
+            let separator = if index == 0 { '?' } else { '&' };
+            write!(f, "{}{}={}", separator, name, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Expected request and its response
+pub struct Mock {
+    method: Method,
+    path: String,
+    query: Vec<(String, String)>,
+    headers: Vec<(HeaderName, HeaderValue)>,
+    body: Option<BodyMatcher>,
+    response: MockResponse,
+    expected: Option<usize>,
+}
+
+type BodyMatcher = Box<dyn Fn(&[u8]) -> bool + Send + Sync>;
+
+impl Mock {
+    /// Requests with `method` and `path`, the path includes the path of the client, answered with 200
+    pub fn new(method: HttpMethod, path: &str) -> Self {
+        let method = match method {
+            HttpMethod::Get => Method::GET,
+            HttpMethod::Post => Method::POST,
+            HttpMethod::Put => Method::PUT,
+            HttpMethod::Patch => Method::PATCH,
+            HttpMethod::Delete => Method::DELETE,
+            HttpMethod::Head => Method::HEAD,
+        };
+        Self {
+            method,
+            path: path.to_string(),
+            query: vec![],
+            headers: vec![],
+            body: None,
+            response: MockResponse::new(200),
+            expected: None,
+        }
+    }
+
+    /// The query must have the pair, other pairs are allowed
+    pub fn with_query(mut self, name: &str, value: &str) -> Self {
+        self.query.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// The request must have the header, panics if it is not a valid header
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((
+            HeaderName::try_from(name).expect("invalid header name"),
+            HeaderValue::try_from(value).expect("invalid header value"),
+        ));
+        self
+    }
+
+    /// The body must be `body`
+    pub fn with_body(self, body: impl Into<Bytes>) -> Self {
+        let body = body.into();
+        self.with_body_matcher(move |received| received == body)
+    }
+
+    /// The body must be the json of `value`, the fields may be in another order
+    pub fn with_json_body<T: Serialize + ?Sized>(self, value: &T) -> Self {
+        let value = serde_json::to_value(value).expect("mock body is not json");
+        self.with_body_matcher(move |received| {
+            serde_json::from_slice::<serde_json::Value>(received).is_ok_and(|body| body == value)
+        })
+    }
+
+    pub fn with_body_matcher(
+        mut self,
+        matcher: impl Fn(&[u8]) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.body = Some(Box::new(matcher));
+        self
+    }
+
+    pub fn respond_with(mut self, response: MockResponse) -> Self {
+        self.response = response;
+        self
+    }
+
+    /// [`MockServer::verify`] checks it is called `times` times
+    pub fn expect(mut self, times: usize) -> Self {
+        self.expected = Some(times);
+        self
+    }
+
+    fn matches(&self, request: &ReceivedRequest) -> bool {
+        self.method == request.method
+            && self.path == request.path
+            && self.query.iter().all(|pair| request.query.contains(pair))
+            && self
+                .headers
+                .iter()
+                .all(|(name, value)| request.headers.get_all(name).iter().any(|v| v == value))
+            && self.body.as_ref().is_none_or(|body| body(&request.body))
+    }
+}
+
+impl Display for Mock {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.method, self.path)
+    }
+}
+
+impl Debug for Mock {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Mock")
+            .field("method", &self.method)
+            .field("path", &self.path)
+            .field("query", &self.query)
+            .field("headers", &self.headers)
+            .field("body", &self.body.is_some())
+            .field("response", &self.response)
+            .field("expected", &self.expected)
+            .finish()
+    }
+}
+
+/// Canned response of a [`Mock`]
+#[derive(Debug, Clone)]
+pub struct MockResponse {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+}
+
+impl MockResponse {
+    /// An empty response, panics if `status` is not a valid status
+    pub fn new(status: u16) -> Self {
+        Self {
+            status: StatusCode::from_u16(status).expect("invalid status"),
+            headers: HeaderMap::new(),
+            body: Bytes::new(),
+        }
+    }
+
+    /// Panics if it is not a valid header
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.append(
+            HeaderName::try_from(name).expect("invalid header name"),
+            HeaderValue::try_from(value).expect("invalid header value"),
+        );
+        self
+    }
+
+    pub fn with_body(mut self, body: impl Into<Bytes>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    /// The json of `value`, with its content type
+    pub fn with_json<T: Serialize + ?Sized>(mut self, value: &T) -> Self {
+        self.body = serde_json::to_vec(value)
+            .expect("mock response is not json")
+            .into();
+        self.headers
+            .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        self
+    }
+
+    fn to_http(&self) -> http::Response<Bytes> {
+        let mut response = http::Response::new(self.body.clone());
+        *response.status_mut() = self.status;
+        *response.headers_mut() = self.headers.clone();
+        response
+    }
+}
@@ -0,0 +1,332 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Request, Response, StatusCode};
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{BoxError, Error, Middleware, Next};
+
+/// What a [`Cassette`] does with the requests
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CassetteMode {
+    /// Send the requests and save them with their responses
+    Record,
+    /// Answer with the saved responses, a request matching none fails
+    Replay,
+    /// Replay if the file exists, record otherwise
+    Once,
+}
+
+/// Parts of the requests compared when replaying, all by default
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Matching {
+    pub method: bool,
+    pub path: bool,
+    /// The query pairs, in any order
+    pub query: bool,
+    pub body: bool,
+}
+
+impl Default for Matching {
+    fn default() -> Self {
+        Self {
+            method: true,
+            path: true,
+            query: true,
+            body: true,
+        }
+    }
+}
+
+/// Records the requests of a client with their responses to a json file, and replays them
+///
+/// Add it last with `builder().layer(...)`, recording sends with the reqwest client of the client,
+/// the host is not saved so the file replays against any host.
+///
+/// ```ignore
+/// let cassette = Cassette::new("tests/cassettes/users.json", CassetteMode::Once)?;
+/// let client = UserClient::builder()
+///     .with_host(format!("http://{}", addr))
+///     .layer(cassette)
+///     .build();
+/// ```
+///
+/// The file is written after every recorded request. Response bodies are read before they are saved,
+/// streamed request bodies are saved empty. The headers of the requests are not saved,
+/// so credentials like `Authorization` or `Cookie` stay out of the file.
+#[derive(Debug, Clone)]
+pub struct Cassette {
+    inner: Arc<Mutex<Inner>>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    path: PathBuf,
+    mode: CassetteMode,
+    matching: Matching,
+    interactions: Vec<Interaction>,
+    played: Vec<bool>,
+}
+
+impl Cassette {
+    /// Cassette of the file at `path`, replaying reads it now
+    pub fn new(path: impl Into<PathBuf>, mode: CassetteMode) -> Result<Self, BoxError> {
+        let path = path.into();
+        let mode = match mode {
+            CassetteMode::Once if path.exists() => CassetteMode::Replay,
+            CassetteMode::Once => CassetteMode::Record,
+            mode => mode,
+        };
+        let interactions: Vec<Interaction> = match mode {
+            CassetteMode::Replay => serde_json::from_slice(&std::fs::read(&path)?)?,
+            _ => vec![],
+        };
+        Ok(Self {
+            inner: Arc::new(Mutex::new(Inner {
+                path,
+                mode,
+                matching: Matching::default(),
+                played: vec![false; interactions.len()],
+                interactions,
+            })),
+        })
+    }
+
+    pub fn with_matching(self, matching: Matching) -> Self {
+        self.inner.lock().unwrap().matching = matching;
+        self
+    }
+
+    /// [`CassetteMode::Record`] or [`CassetteMode::Replay`], `Once` is resolved when created
+    pub fn mode(&self) -> CassetteMode {
+        self.inner.lock().unwrap().mode
+    }
+
+    pub fn path(&self) -> PathBuf {
+        self.inner.lock().unwrap().path.clone()
+    }
+
+    /// The saved response of the first matching interaction not played yet
+    fn play(&self, request: &RecordedRequest) -> Result<(StatusCode, HeaderMap, Bytes), Error> {
+        let mut inner = self.inner.lock().unwrap();
+        let inner = &mut *inner;
+        let index = inner
+            .interactions
+            .iter()
+            .zip(&inner.played)
+            .position(|(interaction, played)| {
+                !played && interaction.request.matches(request, inner.matching)
+            })
+            .ok_or_else(|| {
+                Error::Transport(format!("no recorded interaction matches {}", request).into())
+            })?;
+        inner.played[index] = true;
+        inner.interactions[index].response.to_parts()
+    }
+
+    fn record(&self, interaction: Interaction) -> Result<(), Error> {
+        let mut inner = self.inner.lock().unwrap();
+        inner.interactions.push(interaction);
+        inner.played.push(true);
+        inner.save().map_err(Error::Hook)
+    }
+}
+
+impl Inner {
+    fn save(&self) -> Result<(), BoxError> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.path, serde_json::to_vec_pretty(&self.interactions)?)?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Middleware for Cassette {
+    async fn handle(&self, request: Request, next: Next<'_>) -> Result<Response, Error> {
+        let recorded = RecordedRequest::new(
+            request.method(),
+            request.url(),
+            request.body().and_then(reqwest::Body::as_bytes),
+        );
+        if self.mode() == CassetteMode::Replay {
+            let (status, headers, body) = self.play(&recorded)?;
+            return Ok(crate::build_response(request.url(), status, headers, body));
+        }
+        let response = next.run(request).await?;
+        let url = response.url().clone();
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = response.bytes().await?;
+        self.record(Interaction {
+            request: recorded,
+            response: RecordedResponse::new(status, &headers, &body),
+        })?;
+        Ok(crate::build_response(&url, status, headers, body))
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl crate::blocking::Middleware for Cassette {
+    fn handle(
+        &self,
+        request: crate::blocking::Request,
+        next: crate::blocking::Next<'_>,
+    ) -> Result<crate::blocking::Response, Error> {
+        let recorded = RecordedRequest::new(
+            request.method(),
+            request.url(),
+            request.body().and_then(reqwest::blocking::Body::as_bytes),
+        );
+        if self.mode() == CassetteMode::Replay {
+            let (status, headers, body) = self.play(&recorded)?;
+            return Ok(crate::blocking::build_response(
+                request.url(),
+                status,
+                headers,
+                body,
+            ));
+        }
+        let response = next.run(request)?;
+        let url = response.url().clone();
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = response.bytes()?;
+        self.record(Interaction {
+            request: recorded,
+            response: RecordedResponse::new(status, &headers, &body),
+        })?;
+        Ok(crate::blocking::build_response(&url, status, headers, body))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Interaction {
+    request: RecordedRequest,
+    response: RecordedResponse,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RecordedRequest {
+    method: String,
+    path: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    query: Vec<(String, String)>,
+    #[serde(default)]
+    body: RecordedBody,
+}
+
+impl RecordedRequest {
+    fn new(method: &reqwest::Method, url: &reqwest::Url, body: Option<&[u8]>) -> Self {
+        Self {
+            method: method.to_string(),
+            path: url.path().to_string(),
+            query: url.query_pairs().into_owned().collect(),
+            body: RecordedBody::new(body.unwrap_or_default()),
+        }
+    }
+
+    fn matches(&self, other: &RecordedRequest, matching: Matching) -> bool {
+        let sorted = |query: &[(String, String)]| {
+            let mut query = query.to_vec();
+            query.sort();
+            query
+        };
+        (!matching.method || self.method == other.method)
+            && (!matching.path || self.path == other.path)
+            && (!matching.query || sorted(&self.query) == sorted(&other.query))
+            && (!matching.body || self.body == other.body)
+    }
+}
+
+impl Display for RecordedRequest {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.method, self.path)?;
+        for (index, (name, value)) in self.query.iter().enumerate() {
+            let separator = if index == 0 { '?' } else { '&' };
+            write!(f, "{}{}={}", separator, name, value)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RecordedResponse {
+    status: u16,
+    #[serde(default)]
+    headers: BTreeMap<String, Vec<String>>,
+    #[serde(default)]
+    body: RecordedBody,
+}
+
+impl RecordedResponse {
+    fn new(status: StatusCode, headers: &HeaderMap, body: &[u8]) -> Self {
+        Self {
+            status: status.as_u16(),
+            headers: header_lists(headers),
+            body: RecordedBody::new(body),
+        }
+    }
+
+    fn to_parts(&self) -> Result<(StatusCode, HeaderMap, Bytes), Error> {
+        let invalid =
+            |error: BoxError| Error::Transport(format!("invalid cassette: {}", error).into());
+        let status = StatusCode::from_u16(self.status).map_err(|error| invalid(error.into()))?;
+        let mut headers = HeaderMap::new();
+        for (name, values) in &self.headers {
+            let name = HeaderName::try_from(name).map_err(|error| invalid(error.into()))?;
+            for value in values {
+                let value = HeaderValue::try_from(value).map_err(|error| invalid(error.into()))?;
+                headers.append(name.clone(), value);
+            }
+        }
+        Ok((status, headers, self.body.to_bytes()))
+    }
+}
+
+/// Text bodies are saved as a string, others as an array of bytes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+enum RecordedBody {
+    Text(String),
+    Bytes(Vec<u8>),
+}
+
+impl Default for RecordedBody {
+    fn default() -> Self {
+        RecordedBody::Text(String::new())
+    }
+}
+
+impl RecordedBody {
+    fn new(body: &[u8]) -> Self {
+        match std::str::from_utf8(body) {
+            Ok(text) => RecordedBody::Text(text.to_string()),
+            Err(_) => RecordedBody::Bytes(body.to_vec()),
+        }
+    }
+
+    fn to_bytes(&self) -> Bytes {
+        match self {
+            RecordedBody::Text(text) => Bytes::copy_from_slice(text.as_bytes()),
+            RecordedBody::Bytes(bytes) => Bytes::copy_from_slice(bytes),
+        }
+    }
+}
+
+fn header_lists(headers: &HeaderMap) -> BTreeMap<String, Vec<String>> {
+    let mut lists = BTreeMap::<String, Vec<String>>::new();
+    for (name, value) in headers {
+        lists
+            .entry(name.to_string())
+            .or_default()
+            .push(String::from_utf8_lossy(value.as_bytes()).into_owned());
+    }
+    lists
+}
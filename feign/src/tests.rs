@@ -10,3 +10,216 @@ fn test_host_round() {
     assert_eq!(host_round.host(), "b");
     assert_eq!(host_round.host(), "c");
 }
+
+mod balance {
+    use crate::balance::*;
+    use crate::{Host, HttpMethod, Outcome, RequestContext, Selected};
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    fn hosts(hosts: &[&str]) -> Vec<String> {
+        hosts.iter().map(|host| host.to_string()).collect()
+    }
+
+    fn get(path: &str) -> RequestContext<'_> {
+        RequestContext {
+            method: HttpMethod::Get,
+            path,
+        }
+    }
+
+    fn count<H: Host>(host: &H, times: usize) -> HashMap<String, usize> {
+        let mut counts = HashMap::new();
+        for _ in 0..times {
+            let selected = Selected::new(host, &get("/"));
+            *counts.entry(selected.host().to_string()).or_insert(0) += 1;
+            selected.finish(Outcome::Success);
+        }
+        counts
+    }
+
+    #[test]
+    fn test_weighted_round_robin() {
+        let lb = LoadBalancer::weighted(
+            WeightedRoundRobin::new(),
+            vec![("a".to_string(), 2), ("b".to_string(), 1)],
+        )
+        .unwrap();
+        let picks: Vec<&str> = (0..6).map(|_| lb.select(&get("/"))).collect();
+        assert_eq!(picks, vec!["a", "b", "a", "a", "b", "a"]);
+    }
+
+    #[test]
+    fn test_weight_limit() {
+        let weighted = |weight| {
+            LoadBalancer::weighted(
+                WeightedRoundRobin::new(),
+                vec![("a".to_string(), weight), ("b".to_string(), 1)],
+            )
+        };
+        assert!(weighted(MAX_WEIGHT).is_ok());
+        assert_eq!(
+            weighted(u32::MAX).err().unwrap().to_string(),
+            format!("LoadBalancer weight of a is {}, more than 1000", u32::MAX)
+        );
+    }
+
+    #[test]
+    fn test_replicas_limit() {
+        let lb = LoadBalancer::new(
+            ConsistentHash::new().with_replicas(u32::MAX),
+            hosts(&["a", "b"]),
+        )
+        .unwrap();
+        let ring = 2 * MAX_REPLICAS;
+        assert!(
+            format!("{:?}", lb).contains(&format!("replicas: {}, ring: {}", MAX_REPLICAS, ring))
+        );
+    }
+
+    #[test]
+    fn test_random() {
+        let lb = LoadBalancer::weighted(
+            Random::with_seed(7),
+            vec![("a".to_string(), 3), ("b".to_string(), 1)],
+        )
+        .unwrap();
+        let counts = count(&lb, 4000);
+        assert!(counts["a"] > 2700 && counts["a"] < 3300, "{:?}", counts);
+    }
+
+    #[test]
+    fn test_least_in_flight() {
+        let lb = LoadBalancer::new(LeastInFlight::new(), hosts(&["a", "b", "c"])).unwrap();
+        let first = Selected::new(&lb, &get("/"));
+        let second = Selected::new(&lb, &get("/"));
+        assert_ne!(first.host(), second.host());
+        let third = Selected::new(&lb, &get("/"));
+        assert_eq!(
+            lb.endpoints()
+                .iter()
+                .map(Endpoint::in_flight)
+                .sum::<usize>(),
+            3
+        );
+        let freed = second.host().to_string();
+        drop(second);
+        assert_eq!(lb.select(&get("/")), freed);
+        first.finish(Outcome::Success);
+        third.finish(Outcome::Success);
+    }
+
+    #[test]
+    fn test_consistent_hash() {
+        let lb = LoadBalancer::new(ConsistentHash::new(), hosts(&["a", "b", "c", "d"]))
+            .unwrap()
+            .with_ejection(Ejection::new(1, Duration::from_secs(60)));
+        let paths: Vec<String> = (0..100).map(|i| format!("/user/{}", i)).collect();
+        let before: Vec<String> = paths
+            .iter()
+            .map(|path| lb.select(&get(path)).to_string())
+            .collect();
+        for (path, host) in paths.iter().zip(before.iter()) {
+            assert_eq!(lb.select(&get(path)), host);
+        }
+        lb.feedback("a", Outcome::Failure);
+        for (path, host) in paths.iter().zip(before.iter()) {
+            let now = lb.select(&get(path));
+            assert_ne!(now, "a");
+            if host != "a" {
+                assert_eq!(now, host);
+            }
+        }
+    }
+
+    #[test]
+    fn test_ejection() {
+        let lb = LoadBalancer::new(RoundRobin::new(), hosts(&["a", "b"]))
+            .unwrap()
+            .with_ejection(Ejection::new(2, Duration::from_millis(50)));
+        lb.feedback("a", Outcome::Failure);
+        assert_eq!(count(&lb, 4)["a"], 2);
+        lb.feedback("a", Outcome::Failure);
+        lb.feedback("a", Outcome::Failure);
+        assert_eq!(count(&lb, 4).get("a"), None);
+        std::thread::sleep(Duration::from_millis(60));
+        assert_eq!(count(&lb, 4)["a"], 2);
+
+        lb.feedback("a", Outcome::Failure);
+        lb.feedback("a", Outcome::Failure);
+        lb.feedback("b", Outcome::Failure);
+        lb.feedback("b", Outcome::Failure);
+        assert_eq!(count(&lb, 4)["a"], 2, "all ejected, use all");
+    }
+}
+
+#[cfg(feature = "stream")]
+mod sse {
+    use std::time::Duration;
+
+    use crate::sse::{EventParser, Frame};
+
+    fn frames(chunks: &[&str]) -> Vec<Frame> {
+        let mut parser = EventParser::default();
+        let mut frames = vec![];
+        for chunk in chunks {
+            parser.feed(chunk.as_bytes());
+            while let Some(frame) = parser.next_frame() {
+                frames.push(frame);
+            }
+        }
+        frames
+    }
+
+    fn frame(event: &str, id: Option<&str>, data: &str) -> Frame {
+        Frame {
+            event: event.to_string(),
+            id: id.map(str::to_string),
+            data: data.to_string(),
+            retry: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_events() {
+        assert_eq!(
+            frames(&["data: a\n\n", "event: update\nid: 1\ndata: b\ndata:c\n\n"]),
+            [
+                frame("message", None, "a"),
+                frame("update", Some("1"), "b\nc")
+            ]
+        );
+        // split lines, \r\n split between chunks, comments and events without data
+        assert_eq!(
+            frames(&[
+                ": ping\r",
+                "\n\r\nid: 2\r\nda",
+                "ta: x\r",
+                "\n\r",
+                "\n",
+                "data\n\n"
+            ]),
+            [
+                frame("message", Some("2"), "x"),
+                frame("message", Some("2"), "")
+            ]
+        );
+        let mut retry = frame("message", None, "r");
+        retry.retry = Some(Duration::from_millis(10));
+        assert_eq!(frames(&["retry: 10\ndata: r\n\nretry: x\n"]), [retry]);
+    }
+
+    #[test]
+    fn test_disconnected() {
+        let mut parser = EventParser::default();
+        parser.feed(b"id: 7\ndata: done\n\ndata: partial\n");
+        assert_eq!(parser.next_frame().unwrap().data, "done");
+        assert!(parser.next_frame().is_none());
+        parser.disconnected();
+        parser.feed(b"\ndata: next\n\n");
+        assert_eq!(
+            parser.next_frame().unwrap(),
+            frame("message", Some("7"), "next")
+        );
+    }
+}
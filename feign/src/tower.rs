@@ -0,0 +1,111 @@
+use std::future::{poll_fn, Future};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use reqwest::{Body, Client, Request};
+use tokio::sync::Mutex;
+use tower::{Layer, Service};
+
+use crate::{BoxError, Transport};
+
+/// The request of the services
+pub type HttpRequest = http::Request<Body>;
+
+/// Service sending the requests with a reqwest client, the inner service of [`layer`]
+#[derive(Debug, Clone, Default)]
+pub struct ReqwestService {
+    client: Client,
+}
+
+impl ReqwestService {
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+}
+
+impl Service<HttpRequest> for ReqwestService {
+    type Response = http::Response<Body>;
+    type Error = reqwest::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: HttpRequest) -> Self::Future {
+        let client = self.client.clone();
+        Box::pin(async move {
+            let request = Request::try_from(request)?;
+            client.execute(request).await.map(http::Response::from)
+        })
+    }
+}
+
+/// Send the requests of a client through a tower service
+///
+/// ```ignore
+/// let client = UserClient::builder()
+///     .with_transport(feign::tower::service(my_service))
+///     .build();
+/// ```
+pub fn service<S>(service: S) -> TowerTransport<S> {
+    TowerTransport {
+        service: Mutex::new(service),
+    }
+}
+
+/// Send the requests of a client through tower layers over a default [`ReqwestService`]
+///
+/// ```ignore
+/// let client = UserClient::builder()
+///     .with_transport(feign::tower::layer(
+///         tower::ServiceBuilder::new()
+///             .concurrency_limit(8)
+///             .rate_limit(100, Duration::from_secs(1)),
+///     ))
+///     .build();
+/// ```
+///
+/// Use [`service`] with `layer.layer(ReqwestService::new(client))` to send with another reqwest client.
+pub fn layer<L>(layer: L) -> TowerTransport<L::Service>
+where
+    L: Layer<ReqwestService>,
+{
+    service(layer.layer(ReqwestService::default()))
+}
+
+/// Transport of [`service`] and [`layer`]
+///
+/// All the requests share the service, so the state of the layers, like the permits
+/// of a concurrency limit, applies to all of them. A request waits for the service to be ready,
+/// the service is not cloned.
+#[derive(Debug)]
+pub struct TowerTransport<S> {
+    service: Mutex<S>,
+}
+
+#[async_trait]
+impl<S, B> Transport for TowerTransport<S>
+where
+    S: Service<HttpRequest, Response = http::Response<B>> + Send + 'static,
+    S::Future: Send,
+    S::Error: Into<BoxError>,
+    B: http_body::Body + Send + Sync + 'static,
+    B::Data: Into<Bytes>,
+    B::Error: Into<BoxError>,
+{
+    async fn send(&self, request: HttpRequest) -> Result<http::Response<Body>, BoxError> {
+        // the service is released once called, the response is awaited without it
+        let response = {
+            let mut service = self.service.lock().await;
+            poll_fn(|cx| service.poll_ready(cx))
+                .await
+                .map_err(Into::into)?;
+            service.call(request)
+        };
+        let response = response.await.map_err(Into::into)?;
+        Ok(response.map(Body::wrap))
+    }
+}
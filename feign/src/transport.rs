@@ -0,0 +1,124 @@
+use std::future::Future;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use reqwest::{Body, Client, Request, Response};
+
+use crate::{BoxError, Error};
+
+/// Sends the requests of a client, set it with `builder().with_transport(...)`
+///
+/// The generated methods build the requests with reqwest, the transport only sends them,
+/// so the http stack can be replaced, or the requests answered without a socket.
+/// Without a transport the reqwest client sends them.
+///
+/// ```ignore
+/// struct Hello;
+///
+/// #[async_trait::async_trait]
+/// impl feign::Transport for Hello {
+///     async fn send(&self, request: http::Request<reqwest::Body>) -> Result<http::Response<reqwest::Body>, feign::BoxError> {
+///         Ok(http::Response::new("\"hello\"".into()))
+///     }
+/// }
+///
+/// let client = UserClient::builder().with_transport(Hello).build();
+/// ```
+///
+/// The timeout of a method applies around [`Transport::send`], the responses get the url of their request.
+#[async_trait]
+pub trait Transport: Send + Sync + 'static {
+    async fn send(&self, request: http::Request<Body>) -> Result<http::Response<Body>, BoxError>;
+}
+
+/// Transport sending with a reqwest client
+#[derive(Debug, Clone, Default)]
+pub struct ReqwestTransport {
+    client: Client,
+}
+
+impl ReqwestTransport {
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl Transport for ReqwestTransport {
+    async fn send(&self, request: http::Request<Body>) -> Result<http::Response<Body>, BoxError> {
+        let request = Request::try_from(request)?;
+        Ok(self.client.execute(request).await?.into())
+    }
+}
+
+/// Transport sending with a hyper client, enable the `hyper` feature
+///
+/// [`HyperTransport::new`] speaks plain http, build the hyper client with another connector for https.
+#[cfg(feature = "hyper")]
+#[derive(Debug, Clone)]
+pub struct HyperTransport<C = hyper_util::client::legacy::connect::HttpConnector> {
+    client: hyper_util::client::legacy::Client<C, Body>,
+}
+
+#[cfg(feature = "hyper")]
+impl HyperTransport {
+    pub fn new() -> Self {
+        Self::with_client(
+            hyper_util::client::legacy::Client::builder(hyper_util::rt::TokioExecutor::new())
+                .build_http(),
+        )
+    }
+}
+
+#[cfg(feature = "hyper")]
+impl Default for HyperTransport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "hyper")]
+impl<C> HyperTransport<C> {
+    pub fn with_client(client: hyper_util::client::legacy::Client<C, Body>) -> Self {
+        Self { client }
+    }
+}
+
+#[cfg(feature = "hyper")]
+#[async_trait]
+impl<C> Transport for HyperTransport<C>
+where
+    C: hyper_util::client::legacy::connect::Connect + Clone + Send + Sync + 'static,
+{
+    async fn send(&self, request: http::Request<Body>) -> Result<http::Response<Body>, BoxError> {
+        let response = self.client.request(request).await?;
+        Ok(response.map(Body::wrap))
+    }
+}
+
+/// Send a reqwest request as an `http::Request` with `send`, within the timeout of the request
+pub(crate) async fn send_http<F, Fut, B, E>(request: Request, send: F) -> Result<Response, Error>
+where
+    F: FnOnce(http::Request<Body>) -> Fut,
+    Fut: Future<Output = Result<http::Response<B>, E>>,
+    E: Into<BoxError>,
+    B: http_body::Body + Send + Sync + 'static,
+    B::Data: Into<Bytes>,
+    B::Error: Into<BoxError>,
+{
+    // the timeout is not part of an http::Request
+    let timeout = request.timeout().copied();
+    let url = request.url().clone();
+    let send = send(http::Request::try_from(request)?);
+    let response = match timeout {
+        Some(timeout) => tokio::time::timeout(timeout, send)
+            .await
+            .map_err(|elapsed| Error::Transport(elapsed.into()))?,
+        None => send.await,
+    };
+    let response = response.map_err(|error| Error::Transport(error.into()))?;
+    Ok(Response::from(crate::with_url(
+        response.map(Body::wrap),
+        &url,
+    )))
+}
@@ -144,7 +144,7 @@ async fn main() {
                 id: 456,
                 name: "name".to_owned(),
             },
-            headers: headers,
+            headers,
         })
         .await
     {
@@ -0,0 +1,141 @@
+use std::sync::{Arc, Mutex};
+
+use std::time::Duration;
+
+use feign::re_exports::serde_json::{self, Value};
+use feign::re_exports::{anyhow, reqwest};
+use feign::{client, ClientResult, Error, RequestMeta, RetryPolicy};
+use test_server::User;
+
+type Seen = Arc<Mutex<Vec<(String, String, u16, u32)>>>;
+
+async fn after_receive(
+    response: reqwest::Response,
+    request: &RequestMeta<'_>,
+    state: &Seen,
+) -> ClientResult<reqwest::Response> {
+    state.lock().unwrap().push((
+        request.name.to_string(),
+        request.path.to_string(),
+        response.status().as_u16(),
+        request.attempt,
+    ));
+    if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+        return Err(anyhow::anyhow!("token expired"));
+    }
+    if !request.path.starts_with("/envelope") {
+        return Ok(response);
+    }
+    let status = response.status();
+    let headers = response.headers().clone();
+    let mut envelope: Value = response.json().await?;
+    let data = serde_json::to_vec(&envelope["data"].take())?;
+    Ok(feign::build_response(request.url, status, headers, data))
+}
+
+fn retry() -> RetryPolicy {
+    RetryPolicy::new().with_backoff(Duration::from_millis(1), Duration::from_millis(10))
+}
+
+#[client(path = "/user", after_receive = "after_receive", retry = "retry")]
+pub trait HookClient {
+    #[get(path = "/envelope/<id>")]
+    async fn envelope(&self, #[path] id: i64) -> ClientResult<User>;
+    #[get(path = "/error/<code>")]
+    async fn error(&self, #[path] code: u16) -> Result<String, Error>;
+    #[get(path = "/flaky")]
+    async fn flaky(&self) -> ClientResult<usize>;
+}
+
+fn blocking_after_receive(
+    response: feign::blocking::Response,
+    request: &RequestMeta<'_>,
+    state: &Seen,
+) -> ClientResult<feign::blocking::Response> {
+    state.lock().unwrap().push((
+        request.name.to_string(),
+        request.url.path().to_string(),
+        response.status().as_u16(),
+        request.attempt,
+    ));
+    let status = response.status();
+    let headers = response.headers().clone();
+    let mut envelope: Value = response.json()?;
+    let data = serde_json::to_vec(&envelope["data"].take())?;
+    Ok(feign::blocking::build_response(
+        request.url,
+        status,
+        headers,
+        data,
+    ))
+}
+
+#[client(path = "/user", after_receive = "blocking_after_receive")]
+pub trait BlockingHookClient {
+    #[get(path = "/envelope/<id>")]
+    fn envelope(&self, #[path] id: i64) -> ClientResult<User>;
+}
+
+#[tokio::test]
+async fn test_after_receive() {
+    let addr = test_server::spawn("a").await;
+    let seen = Seen::default();
+    let client = HookClient::builder()
+        .with_host(format!("http://{}", addr))
+        .with_state(seen.clone())
+        .build();
+
+    assert_eq!(
+        client.envelope(3).await.unwrap(),
+        User {
+            id: 3,
+            name: "hello".to_string()
+        }
+    );
+    // the hook fails the response before the status check
+    match client.error(401).await.unwrap_err() {
+        Error::Hook(error) => assert_eq!(error.to_string(), "token expired"),
+        error => panic!("unexpected {:?}", error),
+    }
+    // the status check runs after the hook
+    assert_eq!(client.error(404).await.unwrap_err().status().unwrap(), 404);
+    // every attempt is seen
+    assert_eq!(client.flaky().await.unwrap(), 1);
+
+    let row = |name: &str, path: &str, status: u16, attempt: u32| {
+        (name.to_string(), path.to_string(), status, attempt)
+    };
+    assert_eq!(
+        *seen.lock().unwrap(),
+        vec![
+            row("envelope", "/envelope/3", 200, 1),
+            row("error", "/error/401", 401, 1),
+            row("error", "/error/404", 404, 1),
+            row("flaky", "/flaky", 503, 1),
+            row("flaky", "/flaky", 200, 2),
+        ]
+    );
+}
+
+#[test]
+fn test_blocking_after_receive() {
+    // the blocking client must not run inside the runtime
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let addr = runtime.block_on(test_server::spawn("a"));
+    let seen = Seen::default();
+    let client = BlockingHookClient::builder()
+        .with_host(format!("http://{}", addr))
+        .with_state(seen.clone())
+        .build();
+
+    assert_eq!(client.envelope(5).unwrap().id, 5);
+    assert_eq!(
+        *seen.lock().unwrap(),
+        vec![(
+            "envelope".to_string(),
+            "/user/envelope/5".to_string(),
+            200,
+            1
+        )]
+    );
+}
@@ -0,0 +1,85 @@
+use feign::{client, Args, ClientResult, Error, RequestBody};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use test_server::User;
+
+fn before_send<Body: std::fmt::Debug>(
+    request_builder: feign::blocking::RequestBuilder,
+    _body: RequestBody<Body>,
+    state: &AtomicUsize,
+) -> ClientResult<feign::blocking::RequestBuilder> {
+    state.fetch_add(1, Ordering::SeqCst);
+    Ok(request_builder.header("a", "b"))
+}
+
+fn retry() -> feign::RetryPolicy {
+    feign::RetryPolicy::new().with_backoff(Duration::from_millis(1), Duration::from_millis(10))
+}
+
+#[derive(Args)]
+pub struct PutUserArgs {
+    #[feign_path]
+    pub id: i64,
+    #[feign_query]
+    pub q: String,
+    #[feign_json]
+    pub data: User,
+    #[feign_headers]
+    pub headers: HashMap<String, String>,
+}
+
+#[client(path = "/user", before_send = "before_send", retry = "retry", mock)]
+pub trait BlockingClient {
+    #[get(path = "/find_by_id/<id>")]
+    fn find_by_id(&self, #[path] id: i64) -> ClientResult<User>;
+    #[post(path = "/new_user")]
+    fn new_user(&self, #[json] user: &User) -> ClientResult<String>;
+    #[put(path = "/put_user/<id>")]
+    fn put_user(&self, #[args] args: PutUserArgs) -> ClientResult<User>;
+    #[get(path = "/flaky")]
+    fn flaky(&self) -> Result<usize, Error>;
+    #[get(path = "/slow/<millis>", timeout = "50ms")]
+    fn slow(&self, #[path] millis: u64) -> Result<u64, Error>;
+}
+
+#[test]
+fn test_blocking() {
+    // the blocking client must not run inside the runtime
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let addr = runtime.block_on(test_server::spawn("a"));
+    let client = BlockingClientImpl::builder()
+        .with_host(format!("http://{}", addr))
+        .with_state(AtomicUsize::new(0))
+        .build();
+
+    assert_eq!(client.find_by_id(5).unwrap().id, 5);
+    let user = User {
+        id: 1,
+        name: "link".to_string(),
+    };
+    assert_eq!(client.new_user(&user).unwrap(), "link");
+    let put = client
+        .put_user(PutUserArgs {
+            id: 9,
+            q: "q".to_string(),
+            data: user,
+            headers: HashMap::new(),
+        })
+        .unwrap();
+    assert_eq!(put.id, 9);
+    assert_eq!(client.flaky().unwrap(), 1);
+    assert!(client.slow(200).unwrap_err().is_timeout());
+}
+
+#[test]
+fn test_blocking_mock() {
+    let mock = MockBlockingClient::new().on_find_by_id(|id| {
+        Ok(User {
+            id,
+            name: String::new(),
+        })
+    });
+    let client: &dyn BlockingClient = &mock;
+    assert_eq!(client.find_by_id(4).unwrap().id, 4);
+}
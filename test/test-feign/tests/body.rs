@@ -0,0 +1,96 @@
+use std::sync::{Arc, Mutex};
+
+use feign::re_exports::bytes::Bytes;
+use feign::{client, ClientResult, RequestBody};
+use futures_util::Stream;
+
+type Echo = (Option<String>, String);
+
+async fn before_send<Body: std::fmt::Debug>(
+    request_builder: feign::RequestBuilder,
+    body: RequestBody<Body>,
+    state: &Arc<Mutex<Vec<String>>>,
+) -> ClientResult<feign::RequestBuilder> {
+    state.lock().unwrap().push(format!("{:?}", body));
+    Ok(request_builder)
+}
+
+#[client(path = "/user", before_send = "before_send")]
+pub trait BodyClient {
+    #[post(path = "/raw")]
+    async fn text(&self, #[body] text: &str) -> ClientResult<Echo>;
+    #[post(path = "/raw")]
+    async fn bytes(&self, #[body(content_type = "image/png")] data: Vec<u8>) -> ClientResult<Echo>;
+    #[post(path = "/raw", headers("Content-Type" = "application/x-ndjson"))]
+    async fn overridden(&self, #[body] data: Bytes) -> ClientResult<Echo>;
+    #[post(path = "/raw")]
+    async fn stream(
+        &self,
+        #[body] stream: impl Stream<Item = Result<Bytes, std::io::Error>> + Send + 'static,
+    ) -> ClientResult<Echo>;
+    #[post(path = "/raw")]
+    #[allow(clippy::ptr_arg)]
+    async fn encoded(&self, #[body] data: &Vec<u8>) -> ClientResult<Echo>;
+}
+
+#[client(path = "/user")]
+pub trait BlockingBodyClient {
+    #[post(path = "/raw")]
+    fn text(&self, #[body] text: String) -> ClientResult<Echo>;
+}
+
+#[tokio::test]
+async fn test_body() {
+    let addr = test_server::spawn("a").await;
+    let sent = Arc::new(Mutex::new(Vec::<String>::new()));
+    let client = BodyClient::builder()
+        .with_host(format!("http://{}", addr))
+        .with_state(sent.clone())
+        .build();
+
+    assert_eq!(
+        client.text("hello").await.unwrap(),
+        (
+            Some("text/plain; charset=utf-8".to_string()),
+            "hello".to_string()
+        )
+    );
+    assert_eq!(
+        client.bytes(b"png".to_vec()).await.unwrap(),
+        (Some("image/png".to_string()), "png".to_string())
+    );
+    assert_eq!(
+        client.overridden(Bytes::from("{}")).await.unwrap(),
+        (Some("application/x-ndjson".to_string()), "{}".to_string())
+    );
+    let chunks = vec![Ok(Bytes::from("a")), Ok(Bytes::from("b"))];
+    assert_eq!(
+        client
+            .stream(futures_util::stream::iter(chunks))
+            .await
+            .unwrap(),
+        (
+            Some("application/octet-stream".to_string()),
+            "ab".to_string()
+        )
+    );
+    assert_eq!(
+        *sent.lock().unwrap(),
+        ["Raw(b\"hello\")", "Raw(b\"png\")", "Raw(b\"{}\")", "Stream"]
+    );
+    // only `&str` and `&[u8]` references are sent as they are, others are encoded
+    assert_eq!(
+        client.encoded(&vec![1, 2]).await.unwrap(),
+        (Some("application/json".to_string()), "[1,2]".to_string())
+    );
+}
+
+#[test]
+fn test_blocking_body() {
+    let runtime = feign::re_exports::tokio::runtime::Runtime::new().unwrap();
+    let addr = runtime.block_on(test_server::spawn("a"));
+    let client = BlockingBodyClient::builder()
+        .with_host(format!("http://{}", addr))
+        .build();
+    assert_eq!(client.text("x".to_string()).unwrap().1, "x");
+}
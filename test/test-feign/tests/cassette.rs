@@ -0,0 +1,116 @@
+use std::path::PathBuf;
+
+use feign::testing::{Cassette, CassetteMode, Matching};
+use feign::{client, ClientResult, Error};
+use test_server::User;
+
+#[client(path = "/user")]
+pub trait RecordedClient {
+    #[get(path = "/find_by_id/<id>", headers("Authorization" = "Bearer secret"))]
+    async fn find_by_id(&self, #[path] id: i64) -> ClientResult<feign::Response<User>>;
+    #[post(path = "/new_user")]
+    async fn new_user(&self, #[json] user: &User) -> Result<String, Error>;
+    #[get(path = "/query")]
+    async fn query(&self, #[query] a: i64, #[query] b: i64) -> ClientResult<String>;
+}
+
+#[client(path = "/user")]
+pub trait BlockingRecordedClient {
+    #[get(path = "/find_by_id/<id>")]
+    fn find_by_id(&self, #[path] id: i64) -> ClientResult<User>;
+}
+
+fn cassette_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("feign-{}-{}.json", name, std::process::id()))
+}
+
+fn user(name: &str) -> User {
+    User {
+        id: 1,
+        name: name.to_string(),
+    }
+}
+
+#[tokio::test]
+async fn test_record_and_replay() {
+    let path = cassette_path("record");
+    let addr = test_server::spawn("a").await;
+    let recording = Cassette::new(&path, CassetteMode::Record).unwrap();
+    let client = RecordedClient::builder()
+        .with_host(format!("http://{}", addr))
+        .layer(recording.clone())
+        .build();
+    assert_eq!(client.find_by_id(7).await.unwrap().body.id, 7);
+    assert_eq!(client.new_user(&user("a")).await.unwrap(), "a");
+    assert_eq!(client.query(1, 2).await.unwrap(), "a=1&b=2");
+    assert_eq!(client.find_by_id(7).await.unwrap().body.id, 7);
+    // the request headers are not saved
+    assert!(!std::fs::read_to_string(&path).unwrap().contains("secret"));
+
+    // nothing listens there, the cassette answers
+    let replaying = Cassette::new(&path, CassetteMode::Once).unwrap();
+    assert_eq!(replaying.mode(), CassetteMode::Replay);
+    let client = RecordedClient::builder()
+        .with_host(String::from("http://127.0.0.1:1"))
+        .layer(replaying)
+        .build();
+    assert_eq!(client.new_user(&user("a")).await.unwrap(), "a");
+    assert_eq!(client.query(1, 2).await.unwrap(), "a=1&b=2");
+    assert_eq!(client.find_by_id(7).await.unwrap().body.id, 7);
+    // the replayed response has the url of the request
+    assert_eq!(
+        client.find_by_id(7).await.unwrap().url.as_str(),
+        "http://127.0.0.1:1/user/find_by_id/7"
+    );
+    // every interaction is played once
+    assert!(client.find_by_id(7).await.is_err());
+    // the body is matched
+    match client.new_user(&user("b")).await.unwrap_err() {
+        Error::Transport(error) => assert_eq!(
+            error.to_string(),
+            "no recorded interaction matches POST /user/new_user"
+        ),
+        error => panic!("unexpected {:?}", error),
+    }
+
+    let client = RecordedClient::builder()
+        .with_host(String::from("http://127.0.0.1:1"))
+        .layer(
+            Cassette::new(&path, CassetteMode::Replay)
+                .unwrap()
+                .with_matching(Matching {
+                    body: false,
+                    ..Matching::default()
+                }),
+        )
+        .build();
+    assert_eq!(client.new_user(&user("b")).await.unwrap(), "a");
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_blocking_record_and_replay() {
+    // the blocking client must not run inside the runtime
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let addr = runtime.block_on(test_server::spawn("a"));
+    let path = cassette_path("blocking");
+    let client = BlockingRecordedClient::builder()
+        .with_host(format!("http://{}", addr))
+        .layer(Cassette::new(&path, CassetteMode::Record).unwrap())
+        .build();
+    assert_eq!(client.find_by_id(3).unwrap().id, 3);
+
+    let client = BlockingRecordedClient::builder()
+        .with_host(String::from("http://127.0.0.1:1"))
+        .layer(Cassette::new(&path, CassetteMode::Replay).unwrap())
+        .build();
+    assert_eq!(client.find_by_id(3).unwrap().id, 3);
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn test_replay_missing_cassette() {
+    assert!(Cassette::new(cassette_path("missing"), CassetteMode::Replay).is_err());
+}
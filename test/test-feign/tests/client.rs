@@ -0,0 +1,109 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use feign::re_exports::reqwest;
+use feign::re_exports::reqwest::header::{HeaderMap, HeaderValue};
+use feign::{client, ClientResult};
+use test_server::User;
+
+type Headers = BTreeMap<String, Vec<String>>;
+
+static BUILT: AtomicUsize = AtomicUsize::new(0);
+static BLOCKING_BUILT: AtomicUsize = AtomicUsize::new(0);
+
+async fn client_builder() -> ClientResult<reqwest::Client> {
+    BUILT.fetch_add(1, Ordering::SeqCst);
+    Ok(reqwest::ClientBuilder::new().build()?)
+}
+
+fn blocking_client_builder() -> ClientResult<feign::blocking::Client> {
+    BLOCKING_BUILT.fetch_add(1, Ordering::SeqCst);
+    Ok(feign::blocking::ClientBuilder::new().build()?)
+}
+
+#[client(path = "/user", client_builder = "client_builder")]
+pub trait SharedClient {
+    #[get(path = "/find_by_id/<id>")]
+    async fn find_by_id(&self, #[path] id: i64) -> ClientResult<User>;
+    #[get(path = "/headers")]
+    async fn headers(&self) -> ClientResult<Headers>;
+}
+
+#[client(path = "/user", client_builder = "blocking_client_builder")]
+pub trait BlockingSharedClient {
+    #[get(path = "/find_by_id/<id>")]
+    fn find_by_id(&self, #[path] id: i64) -> ClientResult<User>;
+    #[get(path = "/headers")]
+    fn headers(&self) -> ClientResult<Headers>;
+}
+
+#[tokio::test]
+async fn test_client_built_once() {
+    let addr = test_server::spawn("a").await;
+    let client = SharedClient::builder()
+        .with_host(format!("http://{}", addr))
+        .build();
+
+    for id in 1..=3 {
+        assert_eq!(client.find_by_id(id).await.unwrap().id, id);
+    }
+    client.headers().await.unwrap();
+    assert_eq!(BUILT.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn test_blocking_client_built_once() {
+    let runtime = feign::re_exports::tokio::runtime::Runtime::new().unwrap();
+    let addr = runtime.block_on(test_server::spawn("a"));
+    let client = BlockingSharedClient::builder()
+        .with_host(format!("http://{}", addr))
+        .build();
+
+    for id in 1..=3 {
+        assert_eq!(client.find_by_id(id).unwrap().id, id);
+    }
+    client.headers().unwrap();
+    assert_eq!(BLOCKING_BUILT.load(Ordering::SeqCst), 1);
+}
+
+fn default_headers() -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert("x-client", HeaderValue::from_static("injected"));
+    headers
+}
+
+#[tokio::test]
+async fn test_with_client() {
+    let addr = test_server::spawn("a").await;
+    let client = SharedClient::builder()
+        .with_host(format!("http://{}", addr))
+        .with_client(
+            reqwest::ClientBuilder::new()
+                .default_headers(default_headers())
+                .build()
+                .unwrap(),
+        )
+        .build();
+
+    assert_eq!(
+        client.headers().await.unwrap()["x-client"],
+        vec!["injected"]
+    );
+}
+
+#[test]
+fn test_blocking_with_client() {
+    let runtime = feign::re_exports::tokio::runtime::Runtime::new().unwrap();
+    let addr = runtime.block_on(test_server::spawn("a"));
+    let client = BlockingSharedClient::builder()
+        .with_host(format!("http://{}", addr))
+        .with_client(
+            feign::blocking::ClientBuilder::new()
+                .default_headers(default_headers())
+                .build()
+                .unwrap(),
+        )
+        .build();
+
+    assert_eq!(client.headers().unwrap()["x-client"], vec!["injected"]);
+}
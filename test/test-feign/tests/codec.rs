@@ -0,0 +1,72 @@
+use feign::{client, ClientResult};
+use test_server::User;
+
+#[client(path = "/user", codec = feign::codec::Xml)]
+pub trait CodecClient {
+    #[post(path = "/echo_body")]
+    async fn xml(&self, #[body] user: &User) -> ClientResult<feign::Response<User>>;
+    #[post(path = "/echo_body", codec = feign::codec::MsgPack)]
+    async fn msgpack(&self, #[body] user: &User) -> ClientResult<User>;
+    #[post(path = "/echo_body", codec = feign::codec::Cbor)]
+    async fn cbor(&self, #[body] user: &User) -> ClientResult<User>;
+    #[post(path = "/echo_body", codec = "feign::codec::Form")]
+    async fn form(&self, #[body] user: &User) -> ClientResult<User>;
+    #[post(path = "/echo_body", codec = "feign::codec::Auto")]
+    async fn auto(
+        &self,
+        #[body(content_type = "text/xml; charset=utf-8")] user: &str,
+    ) -> ClientResult<feign::Response<User>>;
+}
+
+#[client(path = "/user")]
+pub trait DefaultCodecClient {
+    #[post(path = "/echo_body")]
+    async fn json(&self, #[body] user: &User) -> ClientResult<User>;
+    #[post(path = "/raw")]
+    async fn raw(
+        &self,
+        #[body(raw)] text: feign::re_exports::bytes::Bytes,
+    ) -> ClientResult<(String, String)>;
+}
+
+#[tokio::test]
+async fn test_codecs() {
+    let addr = test_server::spawn("a").await;
+    let client = CodecClient::builder()
+        .with_host(format!("http://{}", addr))
+        .build();
+    let user = User {
+        id: 3,
+        name: "link".to_string(),
+    };
+
+    let response = client.xml(&user).await.unwrap();
+    assert_eq!(response.body, user);
+    assert_eq!(response.header("content-type"), Some("application/xml"));
+    assert_eq!(
+        response.header("x-accept"),
+        Some("application/xml, text/xml")
+    );
+    assert_eq!(client.msgpack(&user).await.unwrap(), user);
+    assert_eq!(client.cbor(&user).await.unwrap(), user);
+    assert_eq!(client.form(&user).await.unwrap(), user);
+
+    let response = client
+        .auto("<User><id>4</id><name>xml</name></User>")
+        .await
+        .unwrap();
+    assert_eq!(response.body.id, 4);
+    assert_eq!(
+        response.header("x-accept"),
+        Some("application/json, application/xml;q=0.9, application/msgpack;q=0.9, application/cbor;q=0.9")
+    );
+
+    let client = DefaultCodecClient::builder()
+        .with_host(format!("http://{}", addr))
+        .build();
+    assert_eq!(client.json(&user).await.unwrap(), user);
+    assert_eq!(
+        client.raw("x".into()).await.unwrap(),
+        ("application/octet-stream".to_string(), "x".to_string())
+    );
+}
@@ -0,0 +1,67 @@
+use feign::{client, BoxError, ClientResult, Decoder, ResponseView};
+use test_server::User;
+
+/// Status and accept header echoed by the server, with the body length
+struct Summary;
+
+impl Decoder<(u16, String, usize)> for Summary {
+    fn decode(&self, response: ResponseView<'_>) -> Result<(u16, String, usize), BoxError> {
+        let accept = response.header("x-accept").ok_or("no x-accept")?;
+        Ok((
+            response.status.as_u16(),
+            accept.to_string(),
+            response.body.len(),
+        ))
+    }
+}
+
+struct Fails;
+
+impl Decoder<User> for Fails {
+    fn decode(&self, _: ResponseView<'_>) -> Result<User, BoxError> {
+        Err("fails".into())
+    }
+}
+
+#[client(path = "/user")]
+pub trait DecoderClient {
+    #[post(
+        path = "/echo_body",
+        headers("Accept" = "text/plain"),
+        deserialize = Summary
+    )]
+    async fn summary(&self, #[body] body: &str) -> ClientResult<(u16, String, usize)>;
+    #[post(path = "/new_user", deserialize = feign::Text)]
+    async fn text(&self, #[json] user: &User) -> ClientResult<feign::Response<String>>;
+    #[post(path = "/new_user", deserialize = "feign::text")]
+    async fn legacy_text(&self, #[json] user: &User) -> ClientResult<String>;
+    #[get(path = "/find_by_id/<id>", deserialize = Fails)]
+    async fn fails(&self, #[path] id: i64) -> Result<User, feign::Error>;
+}
+
+#[tokio::test]
+async fn test_decoder() {
+    let addr = test_server::spawn("a").await;
+    let client = DecoderClient::builder()
+        .with_host(format!("http://{}", addr))
+        .build();
+    let user = User {
+        id: 1,
+        name: "link".to_string(),
+    };
+
+    assert_eq!(
+        client.summary("abc").await.unwrap(),
+        (200, "text/plain".to_string(), 3)
+    );
+    let response = client.text(&user).await.unwrap();
+    assert_eq!(response.body, "\"link\"");
+    assert_eq!(client.legacy_text(&user).await.unwrap(), "\"link\"");
+    match client.fails(1).await.unwrap_err() {
+        feign::Error::Decode { body, source } => {
+            assert!(body.starts_with(b"{\"id\":1"));
+            assert_eq!(source.to_string(), "fails");
+        }
+        err => panic!("{}", err),
+    }
+}
@@ -0,0 +1,155 @@
+use feign::re_exports::async_trait::async_trait;
+use feign::re_exports::bytes::Bytes;
+use feign::re_exports::{http, reqwest};
+use feign::{client, BoxError, ClientResult, Error, Transport};
+use test_server::{ErrorBody, User};
+
+#[client(path = "/user")]
+pub trait UserClient {
+    #[get(path = "/find_by_id/<id>")]
+    async fn find_by_id(&self, #[path] id: &str) -> Result<User, Error>;
+    #[get(path = "/instance")]
+    async fn instance_as_user(&self) -> Result<User, Error>;
+    #[get(path = "/instance")]
+    async fn instance(&self) -> ClientResult<String>;
+}
+
+fn client(host: String) -> UserClient {
+    UserClient::builder().with_host(host).build()
+}
+
+#[tokio::test]
+async fn test_status_error() {
+    let addr = test_server::spawn("a").await;
+    match client(format!("http://{}", addr)).find_by_id("abc").await {
+        Err(Error::Status(status)) => assert!(status.code.is_client_error()),
+        other => panic!("{:?}", other),
+    }
+}
+
+/// Answers 500 with a body failing after its first chunk
+struct BrokenBody;
+
+#[async_trait]
+impl Transport for BrokenBody {
+    async fn send(
+        &self,
+        _: http::Request<reqwest::Body>,
+    ) -> Result<http::Response<reqwest::Body>, BoxError> {
+        let chunks: Vec<Result<Bytes, std::io::Error>> = vec![
+            Ok(Bytes::from("{\"code\":")),
+            Err(std::io::Error::other("connection reset")),
+        ];
+        let mut response = http::Response::new(reqwest::Body::wrap_stream(
+            futures_util::stream::iter(chunks),
+        ));
+        *response.status_mut() = http::StatusCode::INTERNAL_SERVER_ERROR;
+        Ok(response)
+    }
+}
+
+#[tokio::test]
+async fn test_status_body_read_error() {
+    let client = UserClient::builder()
+        .with_host(String::from("http://memory"))
+        .with_transport(BrokenBody)
+        .build();
+    // the status is not returned with a truncated body
+    match client.find_by_id("1").await {
+        Err(Error::Transport(error)) => assert!(error.to_string().contains("body"), "{}", error),
+        other => panic!("{:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_decode_error() {
+    let addr = test_server::spawn("a").await;
+    match client(format!("http://{}", addr)).instance_as_user().await {
+        Err(Error::Decode { body, .. }) => assert_eq!(&body[..], b"\"a\""),
+        other => panic!("{:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_transport_and_url_errors() {
+    let closed = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    let err = client(format!("http://{}", closed))
+        .find_by_id("1")
+        .await
+        .unwrap_err();
+    assert!(err.is_connect(), "{:?}", err);
+
+    let err = client("not a host".to_string())
+        .find_by_id("1")
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::InvalidUrl { .. }), "{:?}", err);
+}
+
+#[tokio::test]
+async fn test_into_anyhow() {
+    let addr = test_server::spawn("a").await;
+    let client = client(format!("http://{}", addr));
+    assert_eq!(client.instance().await.unwrap(), "a");
+    let err = client.find_by_id("abc").await.unwrap_err();
+    let err = feign::re_exports::anyhow::Error::from(err);
+    assert!(err.downcast_ref::<Error>().is_some());
+}
+
+#[derive(Debug)]
+pub enum ApiError {
+    Server(ErrorBody),
+    Other(Error),
+}
+
+impl From<Error> for ApiError {
+    fn from(err: Error) -> Self {
+        ApiError::Other(err)
+    }
+}
+
+async fn decode_error(
+    _code: feign::re_exports::reqwest::StatusCode,
+    _headers: feign::re_exports::reqwest::header::HeaderMap,
+    body: feign::re_exports::bytes::Bytes,
+) -> ApiError {
+    match feign::re_exports::serde_json::from_slice(&body) {
+        Ok(body) => ApiError::Server(body),
+        Err(err) => ApiError::Other(Error::decode(body, err)),
+    }
+}
+
+#[client(path = "/user")]
+pub trait ErrorClient {
+    #[get(path = "/error/<code>", error_decoder = "decode_error")]
+    async fn error(&self, #[path] code: u16) -> Result<String, ApiError>;
+    #[get(path = "/error/<code>")]
+    async fn error_body(&self, #[path] code: u16) -> Result<String, Error>;
+}
+
+#[tokio::test]
+async fn test_error_body() {
+    let addr = test_server::spawn("a").await;
+    let client = ErrorClient::builder()
+        .with_host(format!("http://{}", addr))
+        .build();
+
+    let err = client.error_body(500).await.unwrap_err();
+    assert_eq!(err.status().unwrap().as_u16(), 500);
+    let body: ErrorBody = err.json().unwrap().unwrap();
+    assert_eq!(body.message, "Internal Server Error");
+
+    match client.error(400).await {
+        Err(ApiError::Server(body)) => assert_eq!(
+            body,
+            ErrorBody {
+                code: 400,
+                message: "Bad Request".to_string()
+            }
+        ),
+        other => panic!("{:?}", other),
+    }
+}
@@ -0,0 +1,63 @@
+use std::collections::{BTreeMap, HashMap};
+
+use feign::{client, Args, ClientResult};
+
+type Headers = BTreeMap<String, Vec<String>>;
+
+#[derive(Args)]
+pub struct HeaderArgs {
+    #[feign_headers]
+    pub headers: HashMap<String, String>,
+}
+
+#[client(
+    path = "/user",
+    headers("User-Agent" = "svc/1.0", "X-Level" = "client", "X-Client" = "a")
+)]
+pub trait HeaderClient {
+    #[get(path = "/headers", headers("X-Level" = "method", "Accept" = "application/json"))]
+    async fn headers(
+        &self,
+        #[header("X-Request-Id")] request_id: &str,
+        #[header("X-Trace")] trace: Option<u64>,
+    ) -> ClientResult<Headers>;
+    #[get(path = "/headers")]
+    async fn iterable(
+        &self,
+        #[header("X-Client")] client: &str,
+        #[headers] headers: Vec<(&str, &str)>,
+        #[args] args: HeaderArgs,
+    ) -> ClientResult<Headers>;
+}
+
+#[tokio::test]
+async fn test_headers() {
+    let addr = test_server::spawn("a").await;
+    let client = HeaderClient::builder()
+        .with_host(format!("http://{}", addr))
+        .build();
+
+    let headers = client.headers("r1", None).await.unwrap();
+    assert_eq!(headers["user-agent"], ["svc/1.0"]);
+    assert_eq!(headers["x-level"], ["method"]);
+    assert_eq!(headers["accept"], ["application/json"]);
+    assert_eq!(headers["x-request-id"], ["r1"]);
+    assert!(!headers.contains_key("x-trace"));
+
+    let headers = client.headers("r2", Some(7)).await.unwrap();
+    assert_eq!(headers["x-trace"], ["7"]);
+
+    let headers = client
+        .iterable(
+            "b",
+            vec![("X-Client", "c"), ("X-Multi", "1"), ("X-Multi", "2")],
+            HeaderArgs {
+                headers: HashMap::from([("X-Level".to_string(), "args".to_string())]),
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(headers["x-client"], ["c"]);
+    assert_eq!(headers["x-multi"], ["1", "2"]);
+    assert_eq!(headers["x-level"], ["args"]);
+}
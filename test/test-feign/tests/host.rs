@@ -0,0 +1,87 @@
+use feign::{client, ClientResult, Host, HostRound};
+use std::fmt::{Display, Formatter};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[client(path = "/user")]
+pub trait InstanceClient {
+    #[get(path = "/instance")]
+    async fn instance(&self) -> ClientResult<String>;
+}
+
+#[tokio::test]
+async fn test_host_round_rotates() {
+    let a = test_server::spawn("a").await;
+    let b = test_server::spawn("b").await;
+    let client = InstanceClient::builder()
+        .with_host(HostRound::new(vec![format!("http://{}", a), format!("http://{}", b)]).unwrap())
+        .build();
+
+    let mut instances = vec![];
+    for _ in 0..4 {
+        instances.push(client.instance().await.unwrap());
+    }
+    assert_eq!(instances, vec!["a", "b", "a", "b"]);
+}
+
+#[derive(Debug)]
+struct Counted {
+    hosts: Vec<String>,
+    count: AtomicUsize,
+}
+
+impl Display for Counted {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Counted")
+    }
+}
+
+impl Host for Counted {
+    fn host(&self) -> &str {
+        let count = self.count.fetch_add(1, Ordering::SeqCst);
+        &self.hosts[count % self.hosts.len()]
+    }
+}
+
+#[tokio::test]
+async fn test_custom_host() {
+    let a = test_server::spawn("a").await;
+    let b = test_server::spawn("b").await;
+    let client = InstanceClient::builder()
+        .with_host(Counted {
+            hosts: vec![format!("http://{}", b), format!("http://{}", a)],
+            count: AtomicUsize::new(0),
+        })
+        .build();
+
+    assert_eq!(client.instance().await.unwrap(), "b");
+    assert_eq!(client.instance().await.unwrap(), "a");
+    assert_eq!(client.instance().await.unwrap(), "b");
+}
+
+#[tokio::test]
+async fn test_load_balancer_ejects_unreachable_host() {
+    use feign::balance::{Ejection, LoadBalancer, RoundRobin};
+    use std::time::Duration;
+
+    let a = test_server::spawn("a").await;
+    // nothing listens on a port just released
+    let closed = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    let client = InstanceClient::builder()
+        .with_host(
+            LoadBalancer::new(
+                RoundRobin::new(),
+                vec![format!("http://{}", closed), format!("http://{}", a)],
+            )
+            .unwrap()
+            .with_ejection(Ejection::new(1, Duration::from_secs(60))),
+        )
+        .build();
+
+    assert!(client.instance().await.is_err());
+    for _ in 0..4 {
+        assert_eq!(client.instance().await.unwrap(), "a");
+    }
+}
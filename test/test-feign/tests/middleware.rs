@@ -0,0 +1,112 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use feign::re_exports::async_trait::async_trait;
+use feign::re_exports::reqwest::header::{HeaderMap, HeaderValue};
+use feign::re_exports::reqwest::{Request, Response, StatusCode};
+use feign::{client, ClientResult, Error, Middleware, Next};
+
+type Headers = BTreeMap<String, Vec<String>>;
+type Trace = Arc<Mutex<Vec<String>>>;
+
+/// Adds a header and records when it runs
+struct Tag(&'static str, Trace);
+
+#[async_trait]
+impl Middleware for Tag {
+    async fn handle(&self, mut request: Request, next: Next<'_>) -> Result<Response, Error> {
+        self.1.lock().unwrap().push(format!("{} >", self.0));
+        request
+            .headers_mut()
+            .append("x-layer", HeaderValue::from_static(self.0));
+        let response = next.run(request).await;
+        self.1.lock().unwrap().push(format!("{} <", self.0));
+        response
+    }
+}
+
+/// Answers `/user/instance` without sending the request
+struct Cached;
+
+#[async_trait]
+impl Middleware for Cached {
+    async fn handle(&self, request: Request, next: Next<'_>) -> Result<Response, Error> {
+        if request.url().path() == "/user/instance" {
+            return Ok(feign::build_response(
+                request.url(),
+                StatusCode::OK,
+                HeaderMap::new(),
+                "\"cached\"",
+            ));
+        }
+        next.run(request).await
+    }
+}
+
+#[client(path = "/user")]
+pub trait LayeredClient {
+    #[get(path = "/headers")]
+    async fn headers(&self) -> ClientResult<Headers>;
+    #[get(path = "/instance")]
+    async fn instance(&self) -> ClientResult<String>;
+}
+
+struct BlockingTag(&'static str);
+
+impl feign::blocking::Middleware for BlockingTag {
+    fn handle(
+        &self,
+        mut request: feign::blocking::Request,
+        next: feign::blocking::Next<'_>,
+    ) -> Result<feign::blocking::Response, Error> {
+        request
+            .headers_mut()
+            .append("x-layer", HeaderValue::from_static(self.0));
+        next.run(request)
+    }
+}
+
+#[client(path = "/user")]
+pub trait BlockingLayeredClient {
+    #[get(path = "/headers")]
+    fn headers(&self) -> ClientResult<Headers>;
+}
+
+#[tokio::test]
+async fn test_middleware() {
+    let addr = test_server::spawn("a").await;
+    let trace = Trace::default();
+    let client = LayeredClient::builder()
+        .with_host(format!("http://{}", addr))
+        .layer(Tag("outer", trace.clone()))
+        .layer(Tag("inner", trace.clone()))
+        .layer(Cached)
+        .build();
+
+    let headers = client.headers().await.unwrap();
+    assert_eq!(
+        headers["x-layer"],
+        vec!["outer".to_string(), "inner".to_string()]
+    );
+    assert_eq!(
+        *trace.lock().unwrap(),
+        vec!["outer >", "inner >", "inner <", "outer <"]
+    );
+    assert_eq!(client.instance().await.unwrap(), "cached");
+}
+
+#[test]
+fn test_blocking_middleware() {
+    // the blocking client must not run inside the runtime
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let addr = runtime.block_on(test_server::spawn("a"));
+    let client = BlockingLayeredClient::builder()
+        .with_host(format!("http://{}", addr))
+        .layer(BlockingTag("blocking"))
+        .build();
+
+    assert_eq!(
+        client.headers().unwrap()["x-layer"],
+        vec!["blocking".to_string()]
+    );
+}
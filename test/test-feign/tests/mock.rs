@@ -0,0 +1,118 @@
+use feign::re_exports::bytes::Bytes;
+use feign::{client, ClientResult};
+use futures_util::{Stream, TryStreamExt};
+use std::sync::Arc;
+use test_server::User;
+
+#[client(path = "/user", mock)]
+pub trait UserClient {
+    #[get(path = "/find_by_id/<id>")]
+    async fn find_by_id(&self, #[path] id: i64) -> ClientResult<User>;
+    #[post(path = "/new_user")]
+    async fn new_user(&self, #[json] user: &User) -> ClientResult<String>;
+}
+
+/// Code under test only knows the trait
+async fn user_name(client: &dyn UserClient, id: i64) -> String {
+    client.find_by_id(id).await.unwrap().name
+}
+
+#[tokio::test]
+async fn test_impl() {
+    let addr = test_server::spawn("a").await;
+    let client: Arc<dyn UserClient> = Arc::new(
+        UserClientImpl::builder()
+            .with_host(format!("http://{}", addr))
+            .build(),
+    );
+    assert_eq!(user_name(&*client, 3).await, "hello");
+    let user = User {
+        id: 1,
+        name: "link".to_string(),
+    };
+    assert_eq!(client.new_user(&user).await.unwrap(), "link");
+}
+
+#[tokio::test]
+async fn test_mock() {
+    let mut calls = 0;
+    let mock = MockUserClient::new()
+        .on_find_by_id(|id| {
+            Ok(User {
+                id,
+                name: format!("mock {}", id),
+            })
+        })
+        .on_new_user(move |user| {
+            calls += 1;
+            Ok(format!("{} {}", user.name, calls))
+        });
+    assert_eq!(user_name(&mock, 3).await, "mock 3");
+    let user = User::default();
+    assert_eq!(mock.new_user(&user).await.unwrap(), " 1");
+    assert_eq!(mock.new_user(&user).await.unwrap(), " 2");
+}
+
+#[tokio::test]
+#[should_panic(expected = "MockUserClient::find_by_id is not programmed")]
+async fn test_mock_not_programmed() {
+    MockUserClient::new().find_by_id(1).await.unwrap();
+}
+
+#[client(path = "/user", keep_trait)]
+pub trait KeptClient {
+    #[get(path = "/headers")]
+    async fn headers(
+        &self,
+        #[header("X-Id")] xid: &str,
+    ) -> ClientResult<std::collections::BTreeMap<String, Vec<String>>>;
+    #[post(path = "/raw")]
+    async fn raw(&self, #[body] text: String) -> ClientResult<(Option<String>, String)>;
+    #[post(path = "/body")]
+    async fn upload(&self, #[part] note: String) -> ClientResult<String>;
+}
+
+#[tokio::test]
+async fn test_keep_trait_argument_attributes() {
+    let addr = test_server::spawn("a").await;
+    let client: Arc<dyn KeptClient> = Arc::new(
+        KeptClientImpl::builder()
+            .with_host(format!("http://{}", addr))
+            .build(),
+    );
+    assert_eq!(client.headers("7").await.unwrap()["x-id"], vec!["7"]);
+    assert_eq!(client.raw("hi".to_string()).await.unwrap().1, "hi");
+    assert!(client
+        .upload("note".to_string())
+        .await
+        .unwrap()
+        .contains("name=\"note\""));
+}
+
+#[client(path = "/user", mock)]
+pub trait UploadClient {
+    #[post(path = "/raw")]
+    async fn upload(
+        &self,
+        #[body] stream: impl Stream<Item = Result<Bytes, std::io::Error>> + Send + 'static,
+    ) -> ClientResult<usize>;
+}
+
+#[tokio::test]
+async fn test_mock_streaming_body() {
+    // the closure receives the stream boxed
+    let (sender, received) = std::sync::mpsc::channel();
+    let mock = MockUploadClient::new().on_upload(move |stream| {
+        sender.send(stream).unwrap();
+        Ok(2)
+    });
+    let chunks = vec![Ok(Bytes::from("a")), Ok(Bytes::from("b"))];
+    assert_eq!(
+        mock.upload(futures_util::stream::iter(chunks))
+            .await
+            .unwrap(),
+        2
+    );
+    let chunks: Vec<Bytes> = received.recv().unwrap().try_collect().await.unwrap();
+    assert_eq!(chunks, [Bytes::from("a"), Bytes::from("b")]);
+}
@@ -0,0 +1,81 @@
+use std::io::Write;
+
+use feign::multipart::Form;
+use feign::{client, ClientResult};
+
+#[client(path = "/user")]
+pub trait UploadClient {
+    #[post(path = "/body")]
+    async fn upload(
+        &self,
+        #[part] user_id: String,
+        #[part(name = "file", filename = "notes.txt", content_type = "text/plain")] notes: Vec<u8>,
+        #[part] avatar: std::path::PathBuf,
+        #[part] stream: feign::re_exports::tokio::fs::File,
+    ) -> ClientResult<String>;
+    #[post(path = "/body")]
+    async fn upload_form(
+        &self,
+        #[multipart] form: Form,
+        #[part] extra: &str,
+    ) -> ClientResult<String>;
+}
+
+#[client(path = "/user")]
+pub trait BlockingUploadClient {
+    #[post(path = "/body")]
+    fn upload(&self, #[part(filename = "a.bin")] data: &[u8]) -> ClientResult<String>;
+}
+
+#[tokio::test]
+async fn test_multipart() {
+    let addr = test_server::spawn("a").await;
+    let client = UploadClient::builder()
+        .with_host(format!("http://{}", addr))
+        .build();
+
+    let dir = std::env::temp_dir().join(format!("feign-multipart-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let avatar = dir.join("avatar.png");
+    std::fs::File::create(&avatar)
+        .unwrap()
+        .write_all(b"png bytes")
+        .unwrap();
+    let stream = dir.join("stream.txt");
+    std::fs::write(&stream, b"streamed").unwrap();
+    let stream = feign::re_exports::tokio::fs::File::open(&stream)
+        .await
+        .unwrap();
+
+    let body = client
+        .upload("7".to_string(), b"hello".to_vec(), avatar, stream)
+        .await
+        .unwrap();
+    assert!(body.contains("name=\"user_id\"\r\n\r\n7\r\n"));
+    assert!(body.contains(
+        "name=\"file\"; filename=\"notes.txt\"\r\nContent-Type: text/plain\r\n\r\nhello\r\n"
+    ));
+    assert!(body.contains(
+        "name=\"avatar\"; filename=\"avatar.png\"\r\nContent-Type: image/png\r\n\r\npng bytes\r\n"
+    ));
+    assert!(body.contains("name=\"stream\"\r\n\r\nstreamed\r\n"));
+
+    let body = client
+        .upload_form(Form::new().text("first", "1"), "2")
+        .await
+        .unwrap();
+    assert!(body.contains("name=\"first\"\r\n\r\n1\r\n"));
+    assert!(body.contains("name=\"extra\"\r\n\r\n2\r\n"));
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_blocking_multipart() {
+    let runtime = feign::re_exports::tokio::runtime::Runtime::new().unwrap();
+    let addr = runtime.block_on(test_server::spawn("a"));
+    let client = BlockingUploadClient::builder()
+        .with_host(format!("http://{}", addr))
+        .build();
+    let body = client.upload(b"bin").unwrap();
+    assert!(body.contains("name=\"data\"; filename=\"a.bin\"\r\n\r\nbin\r\n"));
+}
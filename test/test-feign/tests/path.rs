@@ -0,0 +1,44 @@
+use feign::{client, Args, ClientResult};
+
+#[derive(Args)]
+pub struct EchoArgs {
+    #[feign_path]
+    pub name: String,
+    #[feign_path(raw)]
+    pub rest: String,
+}
+
+#[client(path = "/user/echo")]
+pub trait EchoClient {
+    #[get(path = "/<name>")]
+    async fn echo(&self, #[path] name: &str) -> ClientResult<String>;
+    #[get(path = "/<id>/<rest>")]
+    async fn echo_raw(&self, #[path] id: i64, #[path(raw)] rest: &str) -> ClientResult<String>;
+    #[get(path = "/<name>/<rest>")]
+    async fn echo_args(&self, #[args] args: EchoArgs) -> ClientResult<String>;
+}
+
+#[tokio::test]
+async fn test_path_variables() {
+    let addr = test_server::spawn("a").await;
+    let client = EchoClient::builder()
+        .with_host(format!("http://{}", addr))
+        .build();
+
+    assert_eq!(
+        client.echo("a/b?c#d e%").await.unwrap(),
+        "/user/echo/a%2Fb%3Fc%23d%20e%25"
+    );
+    assert_eq!(client.echo("ü:@").await.unwrap(), "/user/echo/%C3%BC:@");
+    assert_eq!(client.echo_raw(1, "a/b").await.unwrap(), "/user/echo/1/a/b");
+    assert_eq!(
+        client
+            .echo_args(EchoArgs {
+                name: "a b".to_string(),
+                rest: "c/d".to_string(),
+            })
+            .await
+            .unwrap(),
+        "/user/echo/a%20b/c/d"
+    );
+}
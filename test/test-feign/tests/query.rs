@@ -0,0 +1,93 @@
+use std::collections::BTreeMap;
+
+use feign::{client, Args, ClientResult};
+use serde_derive::Serialize;
+
+#[derive(Serialize)]
+pub struct Filter {
+    pub name: String,
+    pub age: Option<u32>,
+    pub tags: Vec<String>,
+}
+
+#[derive(Args)]
+pub struct QueryArgs {
+    #[feign_query]
+    pub from: u32,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Order {
+    Asc,
+}
+
+#[client(path = "/user")]
+pub trait QueryClient {
+    #[get(path = "/query")]
+    async fn query(
+        &self,
+        #[query] page: u32,
+        #[query(name = "page-size")] page_size: Option<u32>,
+        #[query] order: Option<Order>,
+        #[query] ids: &[i64],
+        #[query(join = ",")] fields: Vec<&str>,
+    ) -> ClientResult<String>;
+    #[get(path = "/query")]
+    async fn flatten(
+        &self,
+        #[query(flatten)] filter: &Filter,
+        #[query(flatten)] extra: BTreeMap<&str, &str>,
+        #[query(flatten)] pairs: &[(&str, u32)],
+    ) -> ClientResult<String>;
+    #[get(path = "/query")]
+    async fn with_args(&self, #[query] q: &str, #[args] args: QueryArgs) -> ClientResult<String>;
+    #[get(path = "/query")]
+    async fn pair(
+        &self,
+        #[query] q: (&str, &str),
+        #[query] page: &(&str, u32),
+    ) -> ClientResult<String>;
+}
+
+#[tokio::test]
+async fn test_query() {
+    let addr = test_server::spawn("a").await;
+    let client = QueryClient::builder()
+        .with_host(format!("http://{}", addr))
+        .build();
+
+    assert_eq!(
+        client
+            .query(1, Some(20), Some(Order::Asc), &[1, 2], vec!["id", "name"])
+            .await
+            .unwrap(),
+        "page=1&page-size=20&order=asc&ids=1&ids=2&fields=id%2Cname"
+    );
+    assert_eq!(
+        client.query(1, None, None, &[], vec![]).await.unwrap(),
+        "page=1"
+    );
+
+    let filter = Filter {
+        name: "a b".to_string(),
+        age: None,
+        tags: vec!["x".to_string(), "y".to_string()],
+    };
+    assert_eq!(
+        client
+            .flatten(&filter, BTreeMap::from([("k", "v")]), &[("n", 1)])
+            .await
+            .unwrap(),
+        "name=a+b&tags=x&tags=y&k=v&n=1"
+    );
+    assert_eq!(
+        client.with_args("x", QueryArgs { from: 3 }).await.unwrap(),
+        "q=x&from=3"
+    );
+    // a tuple is one name=value pair
+    assert_eq!(
+        client.pair(("name", "a"), &("page", 2)).await.unwrap(),
+        "name=a&page=2"
+    );
+}
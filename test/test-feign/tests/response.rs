@@ -0,0 +1,48 @@
+use feign::{client, ClientResult};
+use serde_derive::Deserialize;
+use test_server::User;
+
+/// Named like `feign::Response`, deserialized from the body
+#[derive(Debug, Deserialize)]
+pub struct Response {
+    pub id: i64,
+    pub name: String,
+}
+
+#[client(path = "/user")]
+pub trait UserClient {
+    #[get(path = "/find_by_id/<id>")]
+    async fn find_by_id(&self, #[path] id: i64) -> ClientResult<feign::Response<User>>;
+    #[get(path = "/find_by_id/<id>")]
+    async fn find_by_id_raw(&self, #[path] id: i64) -> ClientResult<feign::RawResponse>;
+    #[get(path = "/instance", deserialize = "feign::text")]
+    async fn instance_text(&self) -> ClientResult<feign::Response<String>>;
+    #[get(path = "/find_by_id/<id>")]
+    async fn find_by_id_body(&self, #[path] id: i64) -> ClientResult<Response>;
+}
+
+#[tokio::test]
+async fn test_response() {
+    let addr = test_server::spawn("a").await;
+    let client = UserClient::builder()
+        .with_host(format!("http://{}", addr))
+        .build();
+
+    let response = client.find_by_id(7).await.unwrap();
+    assert_eq!(response.status.as_u16(), 200);
+    assert_eq!(
+        response.url.as_str(),
+        format!("http://{}/user/find_by_id/7", addr)
+    );
+    assert!(response.header("content-length").is_some());
+    assert_eq!(response.body.id, 7);
+
+    let raw = client.find_by_id_raw(8).await.unwrap();
+    assert_eq!(&raw.body[..], br#"{"id":8,"name":"hello"}"#);
+
+    let text = client.instance_text().await.unwrap();
+    assert_eq!(text.into_body(), "\"a\"");
+
+    let body = client.find_by_id_body(9).await.unwrap();
+    assert_eq!((body.id, body.name.as_str()), (9, "hello"));
+}
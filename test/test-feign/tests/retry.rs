@@ -0,0 +1,75 @@
+use feign::balance::{LoadBalancer, RoundRobin};
+use feign::{client, ClientResult, Error, RetryPolicy};
+use std::time::Duration;
+
+fn retry() -> RetryPolicy {
+    RetryPolicy::new().with_backoff(Duration::from_millis(1), Duration::from_millis(10))
+}
+
+#[client(path = "/user", retry = "retry")]
+pub trait RetryClient {
+    #[get(path = "/flaky")]
+    async fn flaky(&self) -> Result<usize, Error>;
+    #[post(path = "/flaky")]
+    async fn flaky_post(&self) -> Result<usize, Error>;
+    #[get(path = "/flaky", retry = "feign::RetryPolicy::none")]
+    async fn flaky_no_retry(&self) -> Result<usize, Error>;
+    #[get(path = "/instance")]
+    async fn instance(&self) -> ClientResult<String>;
+}
+
+#[tokio::test]
+async fn test_retry_status() {
+    let addr = test_server::spawn("a").await;
+    let client = RetryClient::builder()
+        .with_host(format!("http://{}", addr))
+        .build();
+
+    // 503, then 200
+    assert_eq!(client.flaky().await.unwrap(), 1);
+    // POST is not idempotent
+    let err = client.flaky_post().await.unwrap_err();
+    assert_eq!(err.status().unwrap().as_u16(), 503);
+    assert_eq!(client.flaky_post().await.unwrap(), 3);
+    assert_eq!(
+        client
+            .flaky_no_retry()
+            .await
+            .unwrap_err()
+            .status()
+            .unwrap()
+            .as_u16(),
+        503
+    );
+}
+
+#[tokio::test]
+async fn test_retry_moves_to_another_host() {
+    let addr = test_server::spawn("a").await;
+    let closed = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    let client = RetryClient::builder()
+        .with_host(
+            LoadBalancer::new(
+                RoundRobin::new(),
+                vec![format!("http://{}", closed), format!("http://{}", addr)],
+            )
+            .unwrap(),
+        )
+        .build();
+    for _ in 0..4 {
+        assert_eq!(client.instance().await.unwrap(), "a");
+    }
+}
+
+#[test]
+fn test_backoff() {
+    let policy = RetryPolicy::new()
+        .with_backoff(Duration::from_millis(100), Duration::from_millis(350))
+        .with_multiplier(2.0);
+    assert_eq!(policy.backoff(1), Duration::from_millis(100));
+    assert_eq!(policy.backoff(2), Duration::from_millis(200));
+    assert_eq!(policy.backoff(3), Duration::from_millis(350));
+}
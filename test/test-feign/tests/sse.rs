@@ -0,0 +1,148 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use feign::re_exports::async_trait::async_trait;
+use feign::re_exports::bytes::Bytes;
+use feign::re_exports::http;
+use feign::re_exports::reqwest::{self, Request, Response};
+use feign::{client, BoxError, ClientResult, Error, Middleware, Next, RequestMeta, Transport};
+use futures_util::{StreamExt, TryStreamExt};
+use test_server::User;
+
+type Seen = Arc<Mutex<Vec<String>>>;
+
+/// Records the `Last-Event-ID` header of the requests
+struct LastEventId(Seen);
+
+#[async_trait]
+impl Middleware for LastEventId {
+    async fn handle(&self, request: Request, next: Next<'_>) -> Result<Response, Error> {
+        let id = request
+            .headers()
+            .get("last-event-id")
+            .map(|id| id.to_str().unwrap().to_string());
+        self.0
+            .lock()
+            .unwrap()
+            .push(format!("sent {}", id.as_deref().unwrap_or("-")));
+        next.run(request).await
+    }
+}
+
+async fn after_receive(
+    response: Response,
+    request: &RequestMeta<'_>,
+    state: &Seen,
+) -> ClientResult<Response> {
+    state.lock().unwrap().push(format!(
+        "received {} {}",
+        request.name,
+        response.status().as_u16()
+    ));
+    Ok(response)
+}
+
+#[client(path = "/user")]
+pub trait EventClient {
+    #[get(path = "/events")]
+    async fn users(&self) -> ClientResult<feign::EventStream<User>>;
+    #[get(path = "/error/<code>")]
+    async fn error(&self, #[path] code: u16) -> Result<feign::EventStream<User>, feign::Error>;
+}
+
+#[client(path = "/user", after_receive = "after_receive")]
+pub trait HookedEventClient {
+    #[get(path = "/events")]
+    async fn users(&self) -> ClientResult<feign::EventStream<User>>;
+}
+
+#[tokio::test]
+async fn test_events() {
+    let addr = test_server::spawn("a").await;
+    let client = EventClient::builder()
+        .with_host(format!("http://{}", addr))
+        .build();
+
+    // reconnects after the third event with Last-Event-ID: 3
+    let events: Vec<_> = client.users().await.unwrap().try_collect().await.unwrap();
+    assert_eq!(
+        events.iter().map(|event| event.data.id).collect::<Vec<_>>(),
+        [1, 2, 3, 4, 5]
+    );
+    assert_eq!(events[4].event, "user");
+    assert_eq!(events[4].id.as_deref(), Some("5"));
+    assert_eq!(events[4].data.name, "user5");
+    assert_eq!(events[0].retry, None);
+    assert_eq!(events[0].id.as_deref(), Some("1"));
+
+    let error = client.error(500).await.unwrap_err();
+    assert_eq!(error.status().unwrap().as_u16(), 500);
+}
+
+#[tokio::test]
+async fn test_events_reconnect_through_the_client() {
+    let addr = test_server::spawn("a").await;
+    let seen = Seen::default();
+    let client = HookedEventClient::builder()
+        .with_host(format!("http://{}", addr))
+        .layer(LastEventId(seen.clone()))
+        .with_state(seen.clone())
+        .build();
+
+    let events: Vec<_> = client.users().await.unwrap().try_collect().await.unwrap();
+    assert_eq!(events.len(), 5);
+    // the reconnections run the middlewares and the hook, the last one gets 204
+    assert_eq!(
+        *seen.lock().unwrap(),
+        [
+            "sent -",
+            "received users 200",
+            "sent 3",
+            "received users 200",
+            "sent 5",
+            "received users 204"
+        ]
+    );
+}
+
+/// Sends one event, then answers the reconnection with 500 and a body failing to read
+#[derive(Default)]
+struct BrokenReconnect(AtomicUsize);
+
+#[async_trait]
+impl Transport for BrokenReconnect {
+    async fn send(
+        &self,
+        _: http::Request<reqwest::Body>,
+    ) -> Result<http::Response<reqwest::Body>, BoxError> {
+        if self.0.fetch_add(1, Ordering::SeqCst) == 0 {
+            return Ok(http::Response::new(
+                "retry: 1\ndata: {\"id\":1,\"name\":\"a\"}\n\n".into(),
+            ));
+        }
+        let chunks: Vec<Result<Bytes, std::io::Error>> =
+            vec![Err(std::io::Error::other("connection reset"))];
+        let mut response = http::Response::new(reqwest::Body::wrap_stream(
+            futures_util::stream::iter(chunks),
+        ));
+        *response.status_mut() = http::StatusCode::INTERNAL_SERVER_ERROR;
+        Ok(response)
+    }
+}
+
+#[tokio::test]
+async fn test_events_reconnect_body_error() {
+    let client = EventClient::builder()
+        .with_host(String::from("http://memory"))
+        .with_transport(BrokenReconnect::default())
+        .build();
+
+    let events: Vec<_> = client.users().await.unwrap().collect().await;
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].as_ref().unwrap().data.id, 1);
+    assert!(
+        matches!(&events[1], Err(Error::Transport(_))),
+        "{:?}",
+        events[1]
+    );
+}
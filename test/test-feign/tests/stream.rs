@@ -0,0 +1,49 @@
+use feign::re_exports::tokio::io::{AsyncRead, AsyncReadExt};
+use feign::{client, ClientResult};
+use futures_util::TryStreamExt;
+use test_server::User;
+
+#[client(path = "/user")]
+pub trait StreamClient {
+    #[get(path = "/lines/<count>")]
+    async fn bytes(&self, #[path] count: i64) -> ClientResult<feign::ByteStream>;
+    #[get(path = "/lines/<count>")]
+    async fn reader(&self, #[path] count: i64) -> ClientResult<impl AsyncRead + Send + Unpin>;
+    #[get(path = "/lines/<count>")]
+    async fn users(&self, #[path] count: i64) -> ClientResult<feign::JsonLines<User>>;
+    #[get(path = "/error/<code>")]
+    async fn error(&self, #[path] code: u16) -> Result<feign::ByteStream, feign::Error>;
+}
+
+#[tokio::test]
+async fn test_stream() {
+    let addr = test_server::spawn("a").await;
+    let client = StreamClient::builder()
+        .with_host(format!("http://{}", addr))
+        .build();
+
+    let expected = "{\"id\":1,\"name\":\"user1\"}\n\n{\"id\":2,\"name\":\"user2\"}";
+    let chunks: Vec<_> = client.bytes(2).await.unwrap().try_collect().await.unwrap();
+    assert!(chunks.len() > 1);
+    assert_eq!(chunks.concat(), expected.as_bytes());
+
+    let mut body = String::new();
+    client
+        .reader(2)
+        .await
+        .unwrap()
+        .read_to_string(&mut body)
+        .await
+        .unwrap();
+    assert_eq!(body, expected);
+
+    let users: Vec<User> = client.users(3).await.unwrap().try_collect().await.unwrap();
+    assert_eq!(
+        users.iter().map(|user| user.id).collect::<Vec<_>>(),
+        [1, 2, 3]
+    );
+    assert_eq!(users[2].name, "user3");
+
+    let error = client.error(404).await.unwrap_err();
+    assert_eq!(error.status().unwrap().as_u16(), 404);
+}
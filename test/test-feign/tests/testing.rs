@@ -0,0 +1,111 @@
+use feign::testing::{Mock, MockResponse, MockServer};
+use feign::{client, ClientResult, Error, HttpMethod};
+use test_server::User;
+
+#[client(host = "http://mock", path = "/user")]
+pub trait MockedClient {
+    #[get(path = "/find_by_id/<id>")]
+    async fn find_by_id(&self, #[path] id: i64) -> Result<User, Error>;
+    #[post(path = "/new_user", headers("X-Tenant" = "a"))]
+    async fn new_user(&self, #[json] user: &User, #[query] dry_run: bool) -> ClientResult<String>;
+}
+
+#[client(host = "http://mock", path = "/user")]
+pub trait BlockingMockedClient {
+    #[get(path = "/find_by_id/<id>")]
+    fn find_by_id(&self, #[path] id: i64) -> ClientResult<User>;
+}
+
+fn user(id: i64) -> User {
+    User {
+        id,
+        name: "mock".to_string(),
+    }
+}
+
+#[tokio::test]
+async fn test_mock_server() {
+    let server = MockServer::new();
+    let find = server.mock(
+        Mock::new(HttpMethod::Get, "/user/find_by_id/1")
+            .respond_with(MockResponse::new(200).with_json(&user(1)))
+            .expect(2),
+    );
+    server.mock(
+        Mock::new(HttpMethod::Get, "/user/find_by_id/2")
+            .respond_with(MockResponse::new(404))
+            .expect(1),
+    );
+    let new_user = server.mock(
+        Mock::new(HttpMethod::Post, "/user/new_user")
+            .with_query("dry_run", "true")
+            .with_header("x-tenant", "a")
+            .with_json_body(&user(3))
+            .respond_with(MockResponse::new(200).with_json("created")),
+    );
+    let client = MockedClient::builder()
+        .with_transport(server.transport())
+        .build();
+
+    assert_eq!(client.find_by_id(1).await.unwrap(), user(1));
+    assert_eq!(client.find_by_id(1).await.unwrap(), user(1));
+    assert_eq!(
+        client.find_by_id(2).await.unwrap_err().status().unwrap(),
+        404
+    );
+    assert_eq!(client.new_user(&user(3), true).await.unwrap(), "created");
+
+    assert_eq!(find.hits(), 2);
+    assert_eq!(new_user.hits(), 1);
+    let received = server.received();
+    assert_eq!(received.len(), 4);
+    assert_eq!(
+        received[3].query,
+        vec![("dry_run".to_string(), "true".to_string())]
+    );
+    server.verify();
+}
+
+#[tokio::test]
+#[should_panic(expected = "no mock matches POST /user/new_user?dry_run=false")]
+async fn test_mock_server_unmatched() {
+    let server = MockServer::new();
+    server.mock(Mock::new(HttpMethod::Post, "/user/new_user").with_query("dry_run", "true"));
+    let client = MockedClient::builder()
+        .with_transport(server.transport())
+        .build();
+
+    match client
+        .new_user(&user(3), false)
+        .await
+        .unwrap_err()
+        .downcast()
+    {
+        Ok(Error::Transport(_)) => {}
+        error => panic!("unexpected {:?}", error),
+    }
+    server.verify();
+}
+
+#[tokio::test]
+#[should_panic(expected = "GET /user/find_by_id/1 expected 1 calls, got 0")]
+async fn test_mock_server_not_called() {
+    let server = MockServer::new();
+    server.mock(Mock::new(HttpMethod::Get, "/user/find_by_id/1").expect(1));
+    server.verify();
+}
+
+#[test]
+fn test_blocking_mock_server() {
+    let server = MockServer::new();
+    server.mock(
+        Mock::new(HttpMethod::Get, "/user/find_by_id/4")
+            .respond_with(MockResponse::new(200).with_json(&user(4))),
+    );
+    let client = BlockingMockedClient::builder()
+        .with_transport(server.transport())
+        .build();
+
+    assert_eq!(client.find_by_id(4).unwrap(), user(4));
+    server.verify();
+}
@@ -0,0 +1,22 @@
+use feign::{client, Error};
+
+#[client(path = "/user", timeout = "100ms", connect_timeout = "1s")]
+pub trait SlowClient {
+    #[get(path = "/slow/<millis>")]
+    async fn slow(&self, #[path] millis: u64) -> Result<u64, Error>;
+    #[get(path = "/slow/<millis>", timeout = "1s")]
+    async fn slow_long_timeout(&self, #[path] millis: u64) -> Result<u64, Error>;
+}
+
+#[tokio::test]
+async fn test_timeout() {
+    let addr = test_server::spawn("a").await;
+    let client = SlowClient::builder()
+        .with_host(format!("http://{}", addr))
+        .build();
+
+    assert_eq!(client.slow(10).await.unwrap(), 10);
+    let err = client.slow(300).await.unwrap_err();
+    assert!(err.is_timeout(), "{:?}", err);
+    assert_eq!(client.slow_long_timeout(300).await.unwrap(), 300);
+}
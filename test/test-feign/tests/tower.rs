@@ -0,0 +1,107 @@
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use feign::re_exports::http;
+use feign::re_exports::reqwest::header::HeaderValue;
+use feign::tower::HttpRequest;
+use feign::{client, ClientResult, Error};
+use test_server::User;
+use tower::{service_fn, ServiceBuilder};
+
+type Headers = BTreeMap<String, Vec<String>>;
+
+#[client(path = "/user")]
+pub trait TowerClient {
+    #[get(path = "/headers")]
+    async fn headers(&self) -> ClientResult<Headers>;
+    #[get(path = "/find_by_id/<id>")]
+    async fn find_by_id(&self, #[path] id: i64) -> ClientResult<User>;
+    #[get(path = "/slow/<millis>", timeout = "50ms")]
+    async fn slow(&self, #[path] millis: u64) -> Result<u64, Error>;
+}
+
+#[tokio::test]
+async fn test_tower_layer() {
+    let addr = test_server::spawn("a").await;
+    let client = TowerClient::builder()
+        .with_host(format!("http://{}", addr))
+        .with_transport(feign::tower::layer(
+            ServiceBuilder::new()
+                .concurrency_limit(8)
+                .rate_limit(100, Duration::from_secs(1))
+                .map_request(|mut request: HttpRequest| {
+                    request
+                        .headers_mut()
+                        .insert("x-tower", HeaderValue::from_static("yes"));
+                    request
+                }),
+        ))
+        .build();
+
+    assert_eq!(client.headers().await.unwrap()["x-tower"], vec!["yes"]);
+    let (a, b) = tokio::join!(client.find_by_id(1), client.find_by_id(2));
+    assert_eq!((a.unwrap().id, b.unwrap().id), (1, 2));
+    // the timeout of the method still applies
+    assert!(client.slow(200).await.unwrap_err().is_timeout());
+}
+
+#[tokio::test]
+async fn test_tower_service() {
+    // nothing listens there, the service answers
+    let client = TowerClient::builder()
+        .with_host(String::from("http://127.0.0.1:1"))
+        .with_transport(feign::tower::service(service_fn(
+            |request: HttpRequest| async move {
+                let id = request.uri().path().rsplit('/').next().unwrap().to_string();
+                let body = format!("{{\"id\":{},\"name\":\"tower\"}}", id);
+                Ok::<_, Infallible>(http::Response::new(body))
+            },
+        )))
+        .build();
+
+    assert_eq!(
+        client.find_by_id(7).await.unwrap(),
+        User {
+            id: 7,
+            name: "tower".to_string()
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_tower_concurrency_limit() {
+    let in_flight = Arc::new(AtomicUsize::new(0));
+    let max_in_flight = Arc::new(AtomicUsize::new(0));
+    let service = {
+        let (in_flight, max_in_flight) = (in_flight.clone(), max_in_flight.clone());
+        service_fn(move |_: HttpRequest| {
+            let (in_flight, max_in_flight) = (in_flight.clone(), max_in_flight.clone());
+            async move {
+                let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                max_in_flight.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                Ok::<_, Infallible>(http::Response::new(
+                    r#"{"id":1,"name":"tower"}"#.to_string(),
+                ))
+            }
+        })
+    };
+    let client = TowerClient::builder()
+        .with_host(String::from("http://127.0.0.1:1"))
+        .with_transport(feign::tower::service(
+            ServiceBuilder::new().concurrency_limit(1).service(service),
+        ))
+        .build();
+
+    let (a, b, c) = tokio::join!(
+        client.find_by_id(1),
+        client.find_by_id(1),
+        client.find_by_id(1)
+    );
+    assert!(a.is_ok() && b.is_ok() && c.is_ok());
+    assert_eq!(max_in_flight.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,144 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use feign::re_exports::async_trait::async_trait;
+use feign::re_exports::bytes::Bytes;
+use feign::re_exports::{http, reqwest};
+use feign::transport::HyperTransport;
+use feign::{client, BoxError, ClientResult, Error, Transport};
+use futures_util::TryStreamExt;
+use test_server::User;
+
+type Sent = Arc<Mutex<Vec<(String, String, Vec<u8>)>>>;
+
+/// Answers every request with the user of the request body, or user 1
+#[derive(Default)]
+struct InMemory(Sent);
+
+#[async_trait]
+impl Transport for InMemory {
+    async fn send(
+        &self,
+        request: http::Request<reqwest::Body>,
+    ) -> Result<http::Response<reqwest::Body>, BoxError> {
+        let body = request.body().as_bytes().unwrap_or_default().to_vec();
+        self.0.lock().unwrap().push((
+            request.method().to_string(),
+            request.uri().to_string(),
+            body.clone(),
+        ));
+        if request.uri().path().starts_with("/user/slow") {
+            feign::re_exports::tokio::time::sleep(Duration::from_millis(200)).await;
+        }
+        let body = match body.is_empty() {
+            true => "{\"id\":1,\"name\":\"memory\"}".to_string().into_bytes(),
+            false => body,
+        };
+        Ok(http::Response::new(body.into()))
+    }
+}
+
+#[client(host = "http://memory", path = "/user")]
+pub trait TransportClient {
+    #[get(path = "/find_by_id/<id>")]
+    async fn find_by_id(&self, #[path] id: i64) -> ClientResult<User>;
+    #[get(path = "/find_by_id/<id>")]
+    async fn find_response(&self, #[path] id: i64) -> ClientResult<feign::Response<User>>;
+    #[put(path = "/put_user/<id>")]
+    async fn put_user(&self, #[path] id: i64, #[json] user: &User) -> ClientResult<User>;
+    #[get(path = "/slow/<millis>", timeout = "50ms")]
+    async fn slow(&self, #[path] millis: u64) -> Result<User, Error>;
+    #[get(path = "/lines/<count>")]
+    async fn users(&self, #[path] count: i64) -> ClientResult<feign::JsonLines<User>>;
+}
+
+#[tokio::test]
+async fn test_transport() {
+    let sent = Sent::default();
+    let client = TransportClient::builder()
+        .with_transport(InMemory(sent.clone()))
+        .build();
+
+    assert_eq!(client.find_by_id(3).await.unwrap().name, "memory");
+    let user = User {
+        id: 2,
+        name: "put".to_string(),
+    };
+    assert_eq!(client.put_user(2, &user).await.unwrap(), user);
+    assert!(client.slow(200).await.unwrap_err().is_timeout());
+    assert_eq!(
+        *sent.lock().unwrap(),
+        vec![
+            (
+                "GET".to_string(),
+                "http://memory/user/find_by_id/3".to_string(),
+                vec![]
+            ),
+            (
+                "PUT".to_string(),
+                "http://memory/user/put_user/2".to_string(),
+                b"{\"id\":2,\"name\":\"put\"}".to_vec()
+            ),
+            (
+                "GET".to_string(),
+                "http://memory/user/slow/200".to_string(),
+                vec![]
+            ),
+        ]
+    );
+    // the response has the url of the request
+    assert_eq!(
+        client.find_response(4).await.unwrap().url.as_str(),
+        "http://memory/user/find_by_id/4"
+    );
+}
+
+#[tokio::test]
+async fn test_hyper_transport() {
+    let addr = test_server::spawn("a").await;
+    let client = TransportClient::builder()
+        .with_host(format!("http://{}", addr))
+        .with_transport(HyperTransport::new())
+        .build();
+
+    assert_eq!(client.find_by_id(5).await.unwrap().id, 5);
+    let user = User {
+        id: 0,
+        name: "hyper".to_string(),
+    };
+    assert_eq!(client.put_user(4, &user).await.unwrap().id, 4);
+    // the body is streamed
+    let users: Vec<User> = client.users(3).await.unwrap().try_collect().await.unwrap();
+    assert_eq!(users.len(), 3);
+}
+
+struct BlockingInMemory;
+
+impl feign::blocking::Transport for BlockingInMemory {
+    fn send(&self, request: http::Request<Bytes>) -> Result<http::Response<Bytes>, BoxError> {
+        let name = String::from_utf8(request.body().to_vec())?;
+        let body = format!("{{\"id\":1,\"name\":{}}}", name);
+        Ok(http::Response::new(body.into()))
+    }
+}
+
+#[client(host = "http://memory", path = "/user")]
+pub trait BlockingTransportClient {
+    #[post(path = "/new_user")]
+    fn echo(&self, #[json] name: &str) -> ClientResult<User>;
+    #[post(path = "/new_user")]
+    fn echo_response(&self, #[json] name: &str) -> ClientResult<feign::Response<User>>;
+}
+
+#[test]
+fn test_blocking_transport() {
+    let client = BlockingTransportClient::builder()
+        .with_transport(BlockingInMemory)
+        .build();
+
+    assert_eq!(client.echo("blocking").unwrap().name, "blocking");
+    assert_eq!(
+        client.echo_response("url").unwrap().url.as_str(),
+        "http://memory/user/new_user"
+    );
+}
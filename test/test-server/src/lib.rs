@@ -0,0 +1,223 @@
+use std::convert::Infallible;
+use std::net::SocketAddr;
+
+use serde_derive::Deserialize;
+use serde_derive::Serialize;
+use warp::Filter;
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct User {
+    pub id: i64,
+    pub name: String,
+}
+
+/// Error document answered by `/user/error/<code>`
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ErrorBody {
+    pub code: u16,
+    pub message: String,
+}
+
+/// All routes of the test server, `instance` is answered by `/user/instance`
+/// so that clients can tell which server handled a request.
+pub fn routes(
+    instance: &str,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    let find_by_id = warp::path!("user" / "find_by_id" / i64).map(|id| {
+        serde_json::to_string(&User {
+            id,
+            name: "hello".to_string(),
+        })
+        .unwrap()
+    });
+
+    let new_user = warp::post()
+        .and(warp::path!("user" / "new_user"))
+        .and(warp::body::json())
+        .map(move |user: User| serde_json::to_string(&user.name).unwrap());
+
+    let put_user = warp::put()
+        .and(warp::path!("user" / "put_user" / i64))
+        .and(warp::body::json())
+        .map(move |id: i64, mut user: User| {
+            user.id = id;
+            serde_json::to_string(&user).unwrap()
+        });
+
+    let instance = serde_json::to_string(instance).unwrap();
+    let instance = warp::path!("user" / "instance").and_then(move || {
+        let instance = instance.clone();
+        async move { Ok::<_, Infallible>(instance) }
+    });
+
+    let error = warp::path!("user" / "error" / u16).map(|code| {
+        let status = warp::http::StatusCode::from_u16(code).unwrap();
+        warp::reply::with_status(
+            warp::reply::json(&ErrorBody {
+                code,
+                message: status.canonical_reason().unwrap_or("").to_string(),
+            }),
+            status,
+        )
+    });
+
+    // 503 on every other request
+    let calls = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let flaky = warp::path!("user" / "flaky").map(move || {
+        let call = calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        let status = if call.is_multiple_of(2) {
+            warp::http::StatusCode::SERVICE_UNAVAILABLE
+        } else {
+            warp::http::StatusCode::OK
+        };
+        warp::reply::with_status(serde_json::to_string(&call).unwrap(), status)
+    });
+
+    let slow = warp::path!("user" / "slow" / u64).and_then(|millis| async move {
+        tokio::time::sleep(std::time::Duration::from_millis(millis)).await;
+        Ok::<_, Infallible>(serde_json::to_string(&millis).unwrap())
+    });
+
+    // the path as received, still percent-encoded
+    let echo = warp::path!("user" / "echo" / ..)
+        .and(warp::path::full())
+        .map(|path: warp::path::FullPath| serde_json::to_string(path.as_str()).unwrap());
+
+    // the raw query string
+    let query = warp::path!("user" / "query")
+        .and(warp::query::raw().or(warp::any().map(String::new)).unify())
+        .map(|query: String| serde_json::to_string(&query).unwrap());
+
+    // every header, with all its values
+    let headers = warp::path!("user" / "headers")
+        .and(warp::header::headers_cloned())
+        .map(|headers: warp::http::HeaderMap| {
+            let mut map = std::collections::BTreeMap::<String, Vec<String>>::new();
+            for (name, value) in headers.iter() {
+                map.entry(name.to_string())
+                    .or_default()
+                    .push(value.to_str().unwrap().to_string());
+            }
+            serde_json::to_string(&map).unwrap()
+        });
+
+    // the body as received, to check multipart and raw bodies
+    let body = warp::post()
+        .and(warp::path!("user" / "body"))
+        .and(warp::body::bytes())
+        .map(|body: warp::hyper::body::Bytes| {
+            serde_json::to_string(&String::from_utf8_lossy(&body)).unwrap()
+        });
+
+    // the content type and the body
+    let raw = warp::post()
+        .and(warp::path!("user" / "raw"))
+        .and(warp::header::optional::<String>("content-type"))
+        .and(warp::body::bytes())
+        .map(
+            |content_type: Option<String>, body: warp::hyper::body::Bytes| {
+                serde_json::to_string(&(content_type, String::from_utf8_lossy(&body))).unwrap()
+            },
+        );
+
+    // newline-delimited users, sent in chunks that split the lines
+    let lines = warp::path!("user" / "lines" / i64).map(|count| {
+        let body = (1..=count)
+            .map(|id| {
+                serde_json::to_string(&User {
+                    id,
+                    name: format!("user{}", id),
+                })
+                .unwrap()
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        let chunks = body
+            .into_bytes()
+            .chunks(7)
+            .map(|chunk| Ok::<_, Infallible>(chunk.to_vec()))
+            .collect::<Vec<_>>();
+        warp::reply::Response::new(warp::hyper::Body::wrap_stream(futures_util::stream::iter(
+            chunks,
+        )))
+    });
+
+    // three events by connection, five in total, then 204
+    let events = warp::path!("user" / "events")
+        .and(warp::header::optional::<u32>("last-event-id"))
+        .and(warp::header::optional::<String>("accept"))
+        .map(|last: Option<u32>, accept: Option<String>| {
+            let last = last.unwrap_or(0);
+            if last >= 5 || accept.as_deref() != Some("text/event-stream") {
+                return warp::http::Response::builder()
+                    .status(204)
+                    .body(String::new())
+                    .unwrap();
+            }
+            let mut body = String::from("retry: 10\n\n");
+            for id in (last + 1..=5).take(3) {
+                let user = serde_json::to_string(&User {
+                    id: id as i64,
+                    name: format!("user{}", id),
+                })
+                .unwrap();
+                body.push_str(&format!("event: user\nid: {}\ndata: {}\n\n", id, user));
+            }
+            warp::http::Response::builder()
+                .header("content-type", "text/event-stream")
+                .body(body)
+                .unwrap()
+        });
+
+    // the body back with its content type, and the accept header
+    let echo_body = warp::post()
+        .and(warp::path!("user" / "echo_body"))
+        .and(warp::header::optional::<String>("content-type"))
+        .and(warp::header::optional::<String>("accept"))
+        .and(warp::body::bytes())
+        .map(
+            |content_type: Option<String>,
+             accept: Option<String>,
+             body: warp::hyper::body::Bytes| {
+                let mut response = warp::http::Response::builder();
+                if let Some(content_type) = content_type {
+                    response = response.header("content-type", content_type);
+                }
+                if let Some(accept) = accept {
+                    response = response.header("x-accept", accept);
+                }
+                response.body(body.to_vec()).unwrap()
+            },
+        );
+
+    // the user inside a {"data": ...} envelope
+    let envelope = warp::path!("user" / "envelope" / i64).map(|id| {
+        warp::reply::json(&serde_json::json!({
+            "data": User { id, name: "hello".to_string() },
+        }))
+    });
+
+    find_by_id
+        .or(new_user)
+        .or(put_user)
+        .or(instance)
+        .or(error)
+        .or(flaky)
+        .or(slow)
+        .or(echo)
+        .or(query)
+        .or(headers)
+        .or(body)
+        .or(raw)
+        .or(lines)
+        .or(events)
+        .or(echo_body)
+        .or(envelope)
+}
+
+/// Start a server on a random local port, returns the address it listens on.
+pub async fn spawn(instance: &str) -> SocketAddr {
+    let (addr, server) = warp::serve(routes(instance)).bind_ephemeral(([127, 0, 0, 1], 0));
+    tokio::spawn(server);
+    addr
+}